@@ -1,40 +1,126 @@
-use cosmwasm_std::{instantiate2_address, Addr, Api, CanonicalAddr, Storage};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{instantiate2_address, Addr, Api, CanonicalAddr, Order, Storage};
 use cw_multi_test::error::AnyResult;
 use cw_multi_test::AddressGenerator;
+use cw_storage_plus::Map;
 use sha2::digest::Update;
 use sha2::{Digest, Sha256};
 
+use crate::mock::CustomApp;
+
+/// Every address handed out by `MockAddressGenerator`. Stored in app storage so that
+/// addresses of failed instantiations are rolled back with them
+const CONTRACTS: Map<&Addr, GeneratedAddress> = Map::new("mock_address_generator_contracts");
+
+#[cw_serde]
+struct GeneratedAddress {
+    code_id: u64,
+    instance_id: u64,
+}
+
+#[cw_serde]
+pub struct ContractEntry {
+    pub address: Addr,
+    pub code_id: u64,
+    pub instance_id: u64,
+    pub label: String,
+    pub creator: Addr,
+}
+
+/// `(api, code_id, instance_id) -> address`, for `AddressScheme::Custom`
+pub type AddressFn = Box<dyn Fn(&dyn Api, u64, u64) -> AnyResult<Addr> + Send + Sync>;
+
+/// How `MockAddressGenerator` derives non-predictable contract addresses. `Instantiate2`
+/// addresses always follow wasmd
+#[derive(Default)]
+pub enum AddressScheme {
+    /// wasmd's `BuildContractAddressClassic`, bech32 encoded with the app's prefix
+    #[default]
+    Classic,
+    /// `contract{instance_id}`, as in older cw-multi-test versions and fixtures such as
+    /// `factory/contract0/uusk`. `MockAppBuilder` makes its api accept these addresses, but
+    /// they can't be canonicalized, so these contracts can't instantiate2 children or be
+    /// passed as the creator to `predict_contract_address`
+    Sequential,
+    Custom(AddressFn),
+}
+
 #[derive(Default)]
-pub struct MockAddressGenerator;
+pub struct MockAddressGenerator {
+    scheme: AddressScheme,
+}
+
+impl MockAddressGenerator {
+    pub fn new(scheme: AddressScheme) -> Self {
+        Self { scheme }
+    }
+}
 
 impl AddressGenerator for MockAddressGenerator {
     fn contract_address(
         &self,
         api: &dyn Api,
-        _storage: &mut dyn Storage,
+        storage: &mut dyn Storage,
         code_id: u64,
         instance_id: u64,
     ) -> AnyResult<Addr> {
-        let canonical_addr = Self::instantiate_address(code_id, instance_id);
-        Ok(Addr::unchecked(api.addr_humanize(&canonical_addr)?))
+        let addr = match &self.scheme {
+            AddressScheme::Classic => {
+                let canonical_addr = Self::instantiate_address(code_id, instance_id);
+                Addr::unchecked(api.addr_humanize(&canonical_addr)?)
+            }
+            AddressScheme::Sequential => Addr::unchecked(format!("contract{}", instance_id)),
+            AddressScheme::Custom(generate) => generate(api, code_id, instance_id)?,
+        };
+        Self::record(storage, &addr, code_id, instance_id)?;
+        Ok(addr)
     }
 
     fn predictable_contract_address(
         &self,
         api: &dyn Api,
-        _storage: &mut dyn Storage,
-        _code_id: u64,
-        _instance_id: u64,
+        storage: &mut dyn Storage,
+        code_id: u64,
+        instance_id: u64,
         checksum: &[u8],
         creator: &CanonicalAddr,
         salt: &[u8],
     ) -> AnyResult<Addr> {
-        let canonical_addr = instantiate2_address(checksum, creator, salt)?;
-        Ok(Addr::unchecked(api.addr_humanize(&canonical_addr)?))
+        let addr = Self::instantiate2_address(api, checksum, creator, salt)?;
+        Self::record(storage, &addr, code_id, instance_id)?;
+        Ok(addr)
     }
 }
 
 impl MockAddressGenerator {
+    // predictable contract address generator, see `BuildContractAddressPredictable`
+    // implementation in wasmd: https://github.com/CosmWasm/wasmd/blob/main/x/wasm/keeper/addresses.go
+    fn instantiate2_address(
+        api: &dyn Api,
+        checksum: &[u8],
+        creator: &CanonicalAddr,
+        salt: &[u8],
+    ) -> AnyResult<Addr> {
+        let canonical_addr = instantiate2_address(checksum, creator, salt)?;
+        Ok(Addr::unchecked(api.addr_humanize(&canonical_addr)?))
+    }
+
+    pub(crate) fn record(
+        storage: &mut dyn Storage,
+        addr: &Addr,
+        code_id: u64,
+        instance_id: u64,
+    ) -> AnyResult<()> {
+        Ok(CONTRACTS.save(
+            storage,
+            addr,
+            &GeneratedAddress {
+                code_id,
+                instance_id,
+            },
+        )?)
+    }
+
     // non-predictable contract address generator, see `BuildContractAddressClassic`
     // implementation in wasmd: https://github.com/CosmWasm/wasmd/blob/main/x/wasm/keeper/addresses.go#L35-L42
     fn instantiate_address(code_id: u64, instance_id: u64) -> CanonicalAddr {
@@ -51,3 +137,69 @@ impl MockAddressGenerator {
             .into()
     }
 }
+
+/// The address `App` assigns to a contract instantiated with `WasmMsg::Instantiate2` from a
+/// code with `checksum`, by `creator`, with `salt`
+pub fn predict_contract_address(
+    api: &dyn Api,
+    checksum: &[u8],
+    creator: &Addr,
+    salt: &[u8],
+) -> AnyResult<Addr> {
+    let creator = api.addr_canonicalize(creator.as_str())?;
+    MockAddressGenerator::instantiate2_address(api, checksum, &creator, salt)
+}
+
+/// Like `predict_contract_address`, looking up the checksum of the stored `code_id`
+pub fn predict_instantiate2_address(
+    app: &CustomApp,
+    code_id: u64,
+    creator: &Addr,
+    salt: &[u8],
+) -> AnyResult<Addr> {
+    let code_info = app.wrap().query_wasm_code_info(code_id)?;
+    predict_contract_address(app.api(), code_info.checksum.as_slice(), creator, salt)
+}
+
+/// All contracts instantiated in `app`, in instantiation order
+pub fn contracts(app: &CustomApp) -> AnyResult<Vec<ContractEntry>> {
+    let generated = app.read_module(|_, _, storage| {
+        CONTRACTS
+            .range(storage, None, None, Order::Ascending)
+            .collect::<Result<Vec<_>, _>>()
+    })?;
+
+    let mut entries = generated
+        .into_iter()
+        .map(|(address, generated)| {
+            let data = app.contract_data(&address)?;
+            Ok(ContractEntry {
+                address,
+                code_id: generated.code_id,
+                instance_id: generated.instance_id,
+                label: data.label,
+                creator: data.creator,
+            })
+        })
+        .collect::<AnyResult<Vec<ContractEntry>>>()?;
+    entries.sort_by_key(|entry| entry.instance_id);
+    Ok(entries)
+}
+
+/// The first contract instantiated with `label`, if any
+pub fn address_by_label(app: &CustomApp, label: &str) -> AnyResult<Option<Addr>> {
+    Ok(contracts(app)?
+        .into_iter()
+        .find(|entry| entry.label == label)
+        .map(|entry| entry.address))
+}
+
+pub fn label_by_address(app: &CustomApp, address: &Addr) -> Option<String> {
+    app.contract_data(address).ok().map(|data| data.label)
+}
+
+/// The contract's label if `address` is a contract, otherwise the address itself. Useful for
+/// readable failure messages and event dumps
+pub fn display_address(app: &CustomApp, address: &Addr) -> String {
+    label_by_address(app, address).unwrap_or_else(|| address.to_string())
+}
@@ -1,24 +1,144 @@
 use bech32::{decode, encode, FromBase32, ToBase32, Variant};
 use cosmwasm_std::{
-    Addr, Api, CanonicalAddr, RecoverPubkeyError, StdError, StdResult, VerificationError,
+    testing::MockApi, Addr, Api, CanonicalAddr, RecoverPubkeyError, StdError, StdResult,
+    VerificationError,
 };
 
+use ripemd::Ripemd160;
 use sha2::{Digest, Sha256};
 
+use crate::debug::DebugLog;
+
+/// Setting this environment variable prints contract debug messages to stderr
+pub const DEBUG_ENV_VAR: &str = "KUJIRA_MOCK_DEBUG";
+
+/// Canonical address length bounds enforced by the Cosmos SDK
+const MIN_ADDRESS_LENGTH: usize = 1;
+const MAX_ADDRESS_LENGTH: usize = 255;
+
 pub struct MockApiBech32 {
-    prefix: &'static str,
+    prefix: String,
+    variant: Variant,
+    /// Prefixes accepted by `addr_validate` and `addr_canonicalize` besides `prefix`
+    extra_prefixes: Vec<String>,
+    /// Accepts `contract{n}` addresses from `AddressScheme::Sequential` in `addr_validate`
+    sequential_contracts: bool,
+    debug_log: DebugLog,
+    print_debug: bool,
 }
 
 impl MockApiBech32 {
-    pub fn new(prefix: &'static str) -> Self {
-        Self { prefix }
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            variant: Variant::Bech32,
+            extra_prefixes: vec![],
+            sequential_contracts: false,
+            debug_log: DebugLog::default(),
+            print_debug: std::env::var_os(DEBUG_ENV_VAR).is_some(),
+        }
+    }
+
+    /// Encodes and accepts addresses with the given checksum variant, e.g. `Variant::Bech32m`
+    /// for chains that require it
+    pub fn with_variant(mut self, variant: Variant) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    /// Also accepts addresses with `prefix`, e.g. `osmo` for IBC-aware contracts.
+    /// `addr_humanize` keeps using the main prefix
+    pub fn with_accepted_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.extra_prefixes.push(prefix.into());
+        self
+    }
+
+    /// Also accepts `contract0`, `contract1`, ... as valid addresses, so contracts instantiated
+    /// with `AddressScheme::Sequential` can be executed, queried and sent funds. They can't be
+    /// canonicalized
+    pub fn with_sequential_contracts(mut self, sequential_contracts: bool) -> Self {
+        self.sequential_contracts = sequential_contracts;
+        self
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Re-encodes `address` with `prefix`, keeping the same canonical bytes
+    pub fn convert_prefix(&self, address: &str, prefix: &str) -> StdResult<Addr> {
+        let (_, bytes) = self.decode(address)?;
+        self.encode(prefix, &bytes)
+    }
+
+    fn accepts(&self, prefix: &str) -> bool {
+        self.prefix == prefix || self.extra_prefixes.iter().any(|p| p == prefix)
+    }
+
+    fn unknown_prefix(&self, prefix: &str) -> StdError {
+        let mut accepted = vec![self.prefix.as_str()];
+        accepted.extend(self.extra_prefixes.iter().map(String::as_str));
+        invalid_input(&format!(
+            "prefix {} not accepted, expected one of [{}]",
+            prefix,
+            accepted.join(", ")
+        ))
+    }
+
+    fn decode(&self, input: &str) -> StdResult<(String, Vec<u8>)> {
+        if input.chars().any(|c| c.is_ascii_lowercase())
+            && input.chars().any(|c| c.is_ascii_uppercase())
+        {
+            return Err(invalid_input("mixed-case address"));
+        }
+        let (prefix, decoded, variant) =
+            decode(input).map_err(|reason| invalid_input(&reason.to_string()))?;
+        if variant != self.variant {
+            return Err(invalid_input(&format!(
+                "expected {:?} encoding, got {:?}",
+                self.variant, variant
+            )));
+        }
+        let bytes = Vec::<u8>::from_base32(&decoded)
+            .map_err(|reason| invalid_input(&reason.to_string()))?;
+        validate_length(bytes.len())
+            .map_err(|reason| invalid_input(&format!("canonical address {}", reason)))?;
+        Ok((prefix, bytes))
+    }
+
+    fn encode(&self, prefix: &str, bytes: &[u8]) -> StdResult<Addr> {
+        validate_length(bytes.len()).map_err(|reason| {
+            StdError::generic_err(format!("Invalid canonical address: {}", reason))
+        })?;
+        encode(prefix, bytes.to_base32(), self.variant)
+            .map(Addr::unchecked)
+            .map_err(|reason| {
+                StdError::generic_err(format!("Invalid canonical address: {}", reason))
+            })
+    }
+
+    /// Prints debug messages to stderr as well as collecting them
+    pub fn with_print_debug(mut self, print_debug: bool) -> Self {
+        self.print_debug = print_debug;
+        self
+    }
+
+    /// Handle to the debug messages collected from contracts
+    pub fn debug_log(&self) -> DebugLog {
+        self.debug_log.clone()
     }
 }
 
 impl Api for MockApiBech32 {
     fn addr_validate(&self, input: &str) -> StdResult<Addr> {
-        let canonical = self.addr_canonicalize(input)?;
-        let normalized = self.addr_humanize(&canonical)?;
+        if self.sequential_contracts && is_sequential_contract(input) {
+            return Ok(Addr::unchecked(input));
+        }
+        let (prefix, bytes) = self.decode(input)?;
+        if !self.accepts(&prefix) {
+            return Err(self.unknown_prefix(&prefix));
+        }
+        let normalized = self.encode(&prefix, &bytes)?;
         if input != normalized {
             Err(StdError::generic_err(
                 "Invalid input: address not normalized",
@@ -29,75 +149,113 @@ impl Api for MockApiBech32 {
     }
 
     fn addr_canonicalize(&self, input: &str) -> StdResult<CanonicalAddr> {
-        if let Ok((prefix, decoded, Variant::Bech32)) = decode(input) {
-            if prefix == self.prefix {
-                if let Ok(bytes) = Vec::<u8>::from_base32(&decoded) {
-                    return Ok(bytes.into());
-                }
-            }
+        match self.decode(input)? {
+            (prefix, bytes) if self.accepts(&prefix) => Ok(bytes.into()),
+            (prefix, _) => Err(self.unknown_prefix(&prefix)),
         }
-        Err(StdError::generic_err("Invalid input"))
     }
 
     fn addr_humanize(&self, canonical: &CanonicalAddr) -> StdResult<Addr> {
-        if let Ok(encoded) = encode(
-            self.prefix,
-            canonical.as_slice().to_base32(),
-            Variant::Bech32,
-        ) {
-            Ok(Addr::unchecked(encoded))
-        } else {
-            Err(StdError::generic_err("Invalid canonical address"))
-        }
+        self.encode(&self.prefix, canonical.as_slice())
     }
 
+    // Signature verification is independent of the address format, so these defer to
+    // cosmwasm-std's MockApi, which runs the same cosmwasm-crypto code as the chain
+
     fn secp256k1_verify(
         &self,
-        _message_hash: &[u8],
-        _signature: &[u8],
-        _public_key: &[u8],
+        message_hash: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
     ) -> Result<bool, VerificationError> {
-        unimplemented!()
+        MockApi::default().secp256k1_verify(message_hash, signature, public_key)
     }
 
     fn secp256k1_recover_pubkey(
         &self,
-        _message_hash: &[u8],
-        _signature: &[u8],
-        _recovery_param: u8,
+        message_hash: &[u8],
+        signature: &[u8],
+        recovery_param: u8,
     ) -> Result<Vec<u8>, RecoverPubkeyError> {
-        unimplemented!()
+        MockApi::default().secp256k1_recover_pubkey(message_hash, signature, recovery_param)
     }
 
     fn ed25519_verify(
         &self,
-        _message: &[u8],
-        _signature: &[u8],
-        _public_key: &[u8],
+        message: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
     ) -> Result<bool, VerificationError> {
-        unimplemented!()
+        MockApi::default().ed25519_verify(message, signature, public_key)
     }
 
     fn ed25519_batch_verify(
         &self,
-        _messages: &[&[u8]],
-        _signatures: &[&[u8]],
-        _public_keys: &[&[u8]],
+        messages: &[&[u8]],
+        signatures: &[&[u8]],
+        public_keys: &[&[u8]],
     ) -> Result<bool, VerificationError> {
-        unimplemented!()
+        MockApi::default().ed25519_batch_verify(messages, signatures, public_keys)
     }
 
-    fn debug(&self, _message: &str) {
-        unimplemented!()
+    fn debug(&self, message: &str) {
+        let message = self.debug_log.push(message);
+        if self.print_debug {
+            match message.contract {
+                Some(contract) => eprintln!("[{}] {}", contract, message.message),
+                None => eprintln!("{}", message.message),
+            }
+        }
     }
 }
 
 impl MockApiBech32 {
+    /// 32 byte, contract-length address derived from `input`
     pub fn addr_make(&self, input: &str) -> Addr {
         let digest = Sha256::digest(input).to_vec();
-        match encode(self.prefix, digest.to_base32(), Variant::Bech32) {
-            Ok(address) => Addr::unchecked(address),
+        self.addr_make_from_bytes(&digest)
+    }
+
+    /// 20 byte, wallet-length address derived from `input`
+    pub fn addr_make_account(&self, input: &str) -> Addr {
+        let digest = Sha256::digest(input);
+        self.addr_make_from_bytes(&digest[..20])
+    }
+
+    /// Account address of a compressed secp256k1 public key, `ripemd160(sha256(pubkey))` as
+    /// in the Cosmos SDK
+    pub fn addr_make_from_pubkey(&self, public_key: &[u8]) -> Addr {
+        let digest = Ripemd160::digest(Sha256::digest(public_key));
+        self.addr_make_from_bytes(&digest)
+    }
+
+    fn addr_make_from_bytes(&self, bytes: &[u8]) -> Addr {
+        match self.encode(&self.prefix, bytes) {
+            Ok(address) => address,
             Err(reason) => panic!("Generating address failed with reason: {}", reason),
         }
     }
 }
+
+fn invalid_input(reason: &str) -> StdError {
+    StdError::generic_err(format!("Invalid input: {}", reason))
+}
+
+fn validate_length(len: usize) -> Result<(), String> {
+    if (MIN_ADDRESS_LENGTH..=MAX_ADDRESS_LENGTH).contains(&len) {
+        Ok(())
+    } else {
+        Err(format!(
+            "length {} not in range {}..={}",
+            len, MIN_ADDRESS_LENGTH, MAX_ADDRESS_LENGTH
+        ))
+    }
+}
+
+/// `contract{n}` with `n` written without leading zeros, as `AddressScheme::Sequential` does
+fn is_sequential_contract(input: &str) -> bool {
+    input
+        .strip_prefix("contract")
+        .and_then(|n| n.parse::<u64>().ok().map(|parsed| parsed.to_string() == n))
+        == Some(true)
+}
@@ -0,0 +1,304 @@
+//! Assertions over the events, transfers, mints and burns of an `AppResponse` or a contract's
+//! `Response<KujiraMsg>`.
+//!
+//! Event assertions are partial: the expected attributes must all be present on one event of the
+//! same type, in any order, and any other attributes are ignored.
+//!
+//! ```ignore
+//! let res = app.execute_contract(owner.clone(), fin.clone(), &msg, &coins(100, "uusk")).unwrap();
+//! assert_wasm_event(&res, &fin, Event::new("trade").add_attribute("base_amount", "100"));
+//! assert_transfer(&res, &owner, &fin, coins(100, "uusk"));
+//! ```
+
+use std::fmt::Write;
+
+use cosmwasm_std::{Addr, Attribute, BankMsg, Coin, CosmosMsg, Event, Response, Uint128};
+use cw_multi_test::AppResponse;
+use kujira::{DenomMsg, KujiraMsg};
+
+/// A response whose events and bank transfers can be asserted on
+pub trait Emitted {
+    /// Events as they would be seen on chain. A contract `Response` has its attributes under a
+    /// `wasm` event, its events as-is, and `mint`, `burn` and `transfer` events synthesized from
+    /// its messages, matching what `KujiraModule` and the bank module emit
+    fn emitted_events(&self) -> Vec<Event>;
+
+    fn transfers(&self) -> Vec<Transfer>;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transfer {
+    /// `None` for a contract `Response`, where the contract itself is the sender
+    pub sender: Option<Addr>,
+    pub recipient: Addr,
+    pub amount: Vec<Coin>,
+}
+
+impl Emitted for AppResponse {
+    fn emitted_events(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
+    fn transfers(&self) -> Vec<Transfer> {
+        self.events
+            .iter()
+            .filter(|event| event.ty == "transfer")
+            .map(|event| Transfer {
+                sender: attribute(event, "sender").map(Addr::unchecked),
+                recipient: Addr::unchecked(attribute(event, "recipient").unwrap_or_default()),
+                amount: parse_coins(attribute(event, "amount").unwrap_or_default()),
+            })
+            .collect()
+    }
+}
+
+impl Emitted for Response<KujiraMsg> {
+    fn emitted_events(&self) -> Vec<Event> {
+        let mut events = vec![];
+        if !self.attributes.is_empty() {
+            events.push(Event::new("wasm").add_attributes(self.attributes.clone()));
+        }
+        events.extend(self.events.iter().cloned());
+        for sub in &self.messages {
+            match &sub.msg {
+                CosmosMsg::Custom(KujiraMsg::Denom(DenomMsg::Mint {
+                    denom,
+                    amount,
+                    recipient,
+                })) => events.push(mint_event(denom.as_ref(), *amount, recipient)),
+                CosmosMsg::Custom(KujiraMsg::Denom(DenomMsg::Burn { denom, amount })) => {
+                    events.push(burn_event(denom.as_ref(), *amount))
+                }
+                CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => events.push(
+                    Event::new("transfer")
+                        .add_attribute("recipient", to_address)
+                        .add_attribute("amount", coins_to_string(amount)),
+                ),
+                _ => {}
+            }
+        }
+        events
+    }
+
+    fn transfers(&self) -> Vec<Transfer> {
+        self.messages
+            .iter()
+            .filter_map(|sub| match &sub.msg {
+                CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => Some(Transfer {
+                    sender: None,
+                    recipient: Addr::unchecked(to_address),
+                    amount: amount.clone(),
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Asserts that an event of `expected.ty` carries all of `expected`'s attributes
+pub fn assert_event(res: &impl Emitted, expected: Event) {
+    let events = res.emitted_events();
+    if let Err(err) = match_event(&events, &expected) {
+        panic!("{}", err);
+    }
+}
+
+/// Asserts that no event of type `ty` was emitted
+pub fn assert_no_event(res: &impl Emitted, ty: &str) {
+    let events = res.emitted_events();
+    let found: Vec<&Event> = events.iter().filter(|event| event.ty == ty).collect();
+    if !found.is_empty() {
+        let mut out = format!("expected no `{}` event, found {}:\n", ty, found.len());
+        for event in found {
+            for attr in &event.attributes {
+                writeln!(out, "    {}: {}", attr.key, attr.value).unwrap();
+            }
+        }
+        panic!("{}", out);
+    }
+}
+
+/// Asserts that `contract` emitted `expected`. The type is prefixed with `wasm-` as the chain
+/// does, unless it is already `wasm` or `wasm-*`; use `Event::new("wasm")` for the contract's
+/// response attributes
+pub fn assert_wasm_event(res: &AppResponse, contract: &Addr, expected: Event) {
+    let ty = if expected.ty == "wasm" || expected.ty.starts_with("wasm-") {
+        expected.ty.clone()
+    } else {
+        format!("wasm-{}", expected.ty)
+    };
+    let expected = Event::new(ty)
+        .add_attribute("_contract_address", contract)
+        .add_attributes(expected.attributes);
+    assert_event(res, expected);
+}
+
+/// Asserts a bank transfer of exactly `amount` from `sender` to `recipient`. Coin order is
+/// ignored. For a contract `Response` the sender isn't known and only the recipient is checked
+pub fn assert_transfer(
+    res: &impl Emitted,
+    sender: &Addr,
+    recipient: &Addr,
+    amount: impl Into<Vec<Coin>>,
+) {
+    let expected = Transfer {
+        sender: Some(sender.clone()),
+        recipient: recipient.clone(),
+        amount: sorted(amount.into()),
+    };
+    let transfers = res.transfers();
+    let matches = transfers.iter().any(|transfer| {
+        transfer.sender.iter().all(|s| s == sender)
+            && transfer.recipient == *recipient
+            && sorted(transfer.amount.clone()) == expected.amount
+    });
+    if !matches {
+        let mut out = format!(
+            "no transfer matching\n  - {}\nfound {}:\n",
+            display_transfer(&expected),
+            transfers.len()
+        );
+        for transfer in &transfers {
+            writeln!(out, "  + {}", display_transfer(transfer)).unwrap();
+        }
+        panic!("{}", out);
+    }
+}
+
+/// Asserts that `KujiraModule` minted `amount` of `denom` to `recipient`
+pub fn assert_mint(res: &impl Emitted, denom: &str, amount: impl Into<Uint128>, recipient: &Addr) {
+    assert_event(res, mint_event(denom, amount.into(), recipient));
+}
+
+/// Asserts that `KujiraModule` burned `amount` of `denom`
+pub fn assert_burn(res: &impl Emitted, denom: &str, amount: impl Into<Uint128>) {
+    assert_event(res, burn_event(denom, amount.into()));
+}
+
+fn mint_event(denom: &str, amount: Uint128, recipient: &Addr) -> Event {
+    Event::new("mint")
+        .add_attribute("amount", amount)
+        .add_attribute("denom", denom)
+        .add_attribute("recipient", recipient)
+}
+
+fn burn_event(denom: &str, amount: Uint128) -> Event {
+    Event::new("burn")
+        .add_attribute("amount", amount)
+        .add_attribute("denom", denom)
+}
+
+fn match_event(events: &[Event], expected: &Event) -> Result<(), String> {
+    let candidates: Vec<&Event> = events.iter().filter(|e| e.ty == expected.ty).collect();
+    if candidates
+        .iter()
+        .any(|event| contains_all(&event.attributes, &expected.attributes))
+    {
+        return Ok(());
+    }
+
+    let mut out = format!("no `{}` event matching\n", expected.ty);
+    for attr in &expected.attributes {
+        writeln!(out, "    {}: {}", attr.key, attr.value).unwrap();
+    }
+    if candidates.is_empty() {
+        let types: Vec<&str> = events.iter().map(|e| e.ty.as_str()).collect();
+        write!(out, "emitted event types: [{}]", types.join(", ")).unwrap();
+        return Err(out);
+    }
+    writeln!(
+        out,
+        "found {} `{}` event(s):",
+        candidates.len(),
+        expected.ty
+    )
+    .unwrap();
+    for (i, event) in candidates.iter().enumerate() {
+        writeln!(out, "  [{}]", i).unwrap();
+        out.push_str(&attribute_diff(&expected.attributes, &event.attributes));
+    }
+    Err(out)
+}
+
+/// Whether `actual` contains every attribute in `expected`, counting repeated attributes
+fn contains_all(actual: &[Attribute], expected: &[Attribute]) -> bool {
+    let mut used = vec![false; actual.len()];
+    expected.iter().all(
+        |want| match (0..actual.len()).find(|&i| !used[i] && actual[i] == *want) {
+            Some(i) => {
+                used[i] = true;
+                true
+            }
+            None => false,
+        },
+    )
+}
+
+/// `-` lines for expected attributes that are missing or differ, `+` lines for the values found
+/// under those keys, and unmarked lines for everything else on the event
+fn attribute_diff(expected: &[Attribute], actual: &[Attribute]) -> String {
+    let mut out = String::new();
+    for want in expected {
+        if actual.contains(want) {
+            continue;
+        }
+        writeln!(out, "  - {}: {}", want.key, want.value).unwrap();
+        for attr in actual.iter().filter(|attr| attr.key == want.key) {
+            writeln!(out, "  + {}: {}", attr.key, attr.value).unwrap();
+        }
+    }
+    for attr in actual {
+        if expected.contains(attr) || expected.iter().all(|want| want.key != attr.key) {
+            writeln!(out, "    {}: {}", attr.key, attr.value).unwrap();
+        }
+    }
+    out
+}
+
+fn attribute<'a>(event: &'a Event, key: &str) -> Option<&'a str> {
+    event
+        .attributes
+        .iter()
+        .find(|attr| attr.key == key)
+        .map(|attr| attr.value.as_str())
+}
+
+/// Parses the bank module's `100uusk,5ukuji` amount format
+fn parse_coins(amount: &str) -> Vec<Coin> {
+    amount
+        .split(',')
+        .filter(|coin| !coin.is_empty())
+        .map(|coin| {
+            let split = coin
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(coin.len());
+            let (amount, denom) = coin.split_at(split);
+            Coin::new(amount.parse().unwrap_or_default(), denom)
+        })
+        .collect()
+}
+
+fn coins_to_string(coins: &[Coin]) -> String {
+    coins
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn sorted(mut coins: Vec<Coin>) -> Vec<Coin> {
+    coins.sort_by(|a, b| a.denom.cmp(&b.denom));
+    coins
+}
+
+fn display_transfer(transfer: &Transfer) -> String {
+    format!(
+        "{} -> {}: {}",
+        transfer
+            .sender
+            .as_ref()
+            .map_or("(contract)", |sender| sender.as_str()),
+        transfer.recipient,
+        coins_to_string(&transfer.amount)
+    )
+}
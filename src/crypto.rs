@@ -0,0 +1,89 @@
+//! Keypairs for producing signatures that `MockApiBech32` verifies, e.g. for contracts that
+//! accept off-chain signed messages
+
+use cosmwasm_std::Addr;
+use ed25519_zebra::{SigningKey as Ed25519SigningKey, VerificationKey};
+use k256::ecdsa::SigningKey;
+use sha2::{Digest, Sha256};
+
+use crate::api::MockApiBech32;
+
+pub struct Secp256k1KeyPair {
+    signing_key: SigningKey,
+}
+
+impl Secp256k1KeyPair {
+    /// Deterministically derives a keypair from `seed`, so tests get the same keys every run
+    pub fn from_seed(seed: &str) -> Self {
+        let secret = Sha256::digest(seed.as_bytes());
+        let signing_key = SigningKey::from_slice(&secret)
+            .unwrap_or_else(|reason| panic!("Deriving secp256k1 key failed: {}", reason));
+        Self { signing_key }
+    }
+
+    /// 33 byte compressed SEC1 public key, as used by Cosmos SDK accounts
+    pub fn public_key(&self) -> Vec<u8> {
+        self.signing_key
+            .verifying_key()
+            .to_encoded_point(true)
+            .as_bytes()
+            .to_vec()
+    }
+
+    /// Account address of this keypair, as the chain would derive it
+    pub fn address(&self, api: &MockApiBech32) -> Addr {
+        api.addr_make_from_pubkey(&self.public_key())
+    }
+
+    /// 65 byte uncompressed SEC1 public key, as returned by `secp256k1_recover_pubkey`
+    pub fn uncompressed_public_key(&self) -> Vec<u8> {
+        self.signing_key
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes()
+            .to_vec()
+    }
+
+    /// Signs the sha256 hash of `message`, returning the 64 byte `r || s` signature and its
+    /// recovery param
+    pub fn sign(&self, message: &[u8]) -> (Vec<u8>, u8) {
+        self.sign_hash(&Sha256::digest(message))
+    }
+
+    /// Signs a 32 byte message hash, returning the 64 byte `r || s` signature and its
+    /// recovery param
+    pub fn sign_hash(&self, message_hash: &[u8]) -> (Vec<u8>, u8) {
+        let (signature, recovery_id) = self
+            .signing_key
+            .sign_prehash_recoverable(message_hash)
+            .unwrap_or_else(|reason| panic!("Signing with secp256k1 failed: {}", reason));
+        (signature.to_bytes().to_vec(), recovery_id.to_byte())
+    }
+}
+
+pub struct Ed25519KeyPair {
+    signing_key: Ed25519SigningKey,
+}
+
+impl Ed25519KeyPair {
+    /// Deterministically derives a keypair from `seed`, so tests get the same keys every run
+    pub fn from_seed(seed: &str) -> Self {
+        let mut secret = [0u8; 32];
+        secret.copy_from_slice(&Sha256::digest(seed.as_bytes()));
+        Self {
+            signing_key: Ed25519SigningKey::from(secret),
+        }
+    }
+
+    /// 32 byte public key
+    pub fn public_key(&self) -> Vec<u8> {
+        let key: [u8; 32] = VerificationKey::from(&self.signing_key).into();
+        key.to_vec()
+    }
+
+    /// Signs `message`, returning the 64 byte signature
+    pub fn sign(&self, message: &[u8]) -> Vec<u8> {
+        let signature: [u8; 64] = self.signing_key.sign(message).into();
+        signature.to_vec()
+    }
+}
@@ -0,0 +1,159 @@
+//! Collects `Api::debug` output from contracts running inside a `CustomApp`.
+//!
+//! `Api::debug` only receives the message, so contracts must be stored through
+//! [`DebugLog::wrap`] for their messages to be attributed to a contract address.
+//! `MockAppBuilder::with_code` does this for you; codes stored with `App::store_code` need
+//! wrapping by hand.
+//!
+//! Messages are attributed to the innermost wrapped contract being called. An unwrapped
+//! contract called or queried by a wrapped one logs under the caller's address, so wrap every
+//! code when relying on `DebugLog::messages_for`.
+
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+use cosmwasm_std::{Addr, Binary, CustomQuery, Deps, DepsMut, Env, MessageInfo, Reply, Response};
+use cw_multi_test::{error::AnyResult, Contract};
+use schemars::JsonSchema;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DebugMessage {
+    /// The innermost wrapped contract being called when the message was emitted, see the
+    /// module docs
+    pub contract: Option<Addr>,
+    pub message: String,
+}
+
+#[derive(Default)]
+struct DebugLogState {
+    messages: Vec<DebugMessage>,
+    /// Contracts currently being called, innermost last
+    contracts: Vec<Addr>,
+}
+
+/// Shared handle to the debug messages collected by a `MockApiBech32`
+#[derive(Clone, Default)]
+pub struct DebugLog {
+    state: Arc<Mutex<DebugLogState>>,
+}
+
+impl DebugLog {
+    pub(crate) fn push(&self, message: &str) -> DebugMessage {
+        let mut state = self.state.lock().unwrap();
+        let message = DebugMessage {
+            contract: state.contracts.last().cloned(),
+            message: message.to_string(),
+        };
+        state.messages.push(message.clone());
+        message
+    }
+
+    /// All messages collected so far
+    pub fn messages(&self) -> Vec<DebugMessage> {
+        self.state.lock().unwrap().messages.clone()
+    }
+
+    /// Messages emitted by `contract`
+    pub fn messages_for(&self, contract: &Addr) -> Vec<String> {
+        self.state
+            .lock()
+            .unwrap()
+            .messages
+            .iter()
+            .filter(|m| m.contract.as_ref() == Some(contract))
+            .map(|m| m.message.clone())
+            .collect()
+    }
+
+    /// Removes and returns all messages collected so far, e.g. to assert on a single execution
+    pub fn take(&self) -> Vec<DebugMessage> {
+        std::mem::take(&mut self.state.lock().unwrap().messages)
+    }
+
+    pub fn clear(&self) {
+        self.state.lock().unwrap().messages.clear();
+    }
+
+    /// Wraps a contract so that its debug messages are recorded against its address
+    pub fn wrap<T, Q>(&self, contract: Box<dyn Contract<T, Q>>) -> Box<dyn Contract<T, Q>>
+    where
+        T: Clone + Debug + PartialEq + JsonSchema + 'static,
+        Q: CustomQuery + 'static,
+    {
+        Box::new(DebugContract {
+            log: self.clone(),
+            contract,
+        })
+    }
+
+    fn enter(&self, contract: &Addr) -> DebugScope {
+        self.state.lock().unwrap().contracts.push(contract.clone());
+        DebugScope { log: self.clone() }
+    }
+}
+
+/// Marks the end of a contract call when dropped
+struct DebugScope {
+    log: DebugLog,
+}
+
+impl Drop for DebugScope {
+    fn drop(&mut self) {
+        self.log.state.lock().unwrap().contracts.pop();
+    }
+}
+
+struct DebugContract<T, Q> {
+    log: DebugLog,
+    contract: Box<dyn Contract<T, Q>>,
+}
+
+impl<T, Q> Contract<T, Q> for DebugContract<T, Q>
+where
+    T: Clone + Debug + PartialEq + JsonSchema,
+    Q: CustomQuery,
+{
+    fn execute(
+        &self,
+        deps: DepsMut<Q>,
+        env: Env,
+        info: MessageInfo,
+        msg: Vec<u8>,
+    ) -> AnyResult<Response<T>> {
+        let _scope = self.log.enter(&env.contract.address);
+        self.contract.execute(deps, env, info, msg)
+    }
+
+    fn instantiate(
+        &self,
+        deps: DepsMut<Q>,
+        env: Env,
+        info: MessageInfo,
+        msg: Vec<u8>,
+    ) -> AnyResult<Response<T>> {
+        let _scope = self.log.enter(&env.contract.address);
+        self.contract.instantiate(deps, env, info, msg)
+    }
+
+    fn query(&self, deps: Deps<Q>, env: Env, msg: Vec<u8>) -> AnyResult<Binary> {
+        let _scope = self.log.enter(&env.contract.address);
+        self.contract.query(deps, env, msg)
+    }
+
+    fn sudo(&self, deps: DepsMut<Q>, env: Env, msg: Vec<u8>) -> AnyResult<Response<T>> {
+        let _scope = self.log.enter(&env.contract.address);
+        self.contract.sudo(deps, env, msg)
+    }
+
+    fn reply(&self, deps: DepsMut<Q>, env: Env, msg: Reply) -> AnyResult<Response<T>> {
+        let _scope = self.log.enter(&env.contract.address);
+        self.contract.reply(deps, env, msg)
+    }
+
+    fn migrate(&self, deps: DepsMut<Q>, env: Env, msg: Vec<u8>) -> AnyResult<Response<T>> {
+        let _scope = self.log.enter(&env.contract.address);
+        self.contract.migrate(deps, env, msg)
+    }
+}
@@ -0,0 +1,194 @@
+//! Best-effort decoding of raw contract storage back into cw-storage-plus `Item`s and `Map`s.
+//!
+//! cw-storage-plus stores an `Item` under its bare name, and a `Map` entry under
+//! `len(namespace) | namespace | len(k1) | k1 | ... | kn`, where every key part except the
+//! last is prefixed with its length as a big-endian u16. The encoding is not self-describing,
+//! so a key is read as a `Map` entry only when its prefix spells out a printable namespace.
+
+use std::{
+    convert::{TryFrom, TryInto},
+    fmt,
+};
+
+use cosmwasm_std::{Order, Record, Storage};
+use serde_json::Value;
+
+/// One part of a composite `Map` key
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct KeyPart(pub Vec<u8>);
+
+impl KeyPart {
+    pub fn as_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.0)
+            .ok()
+            .filter(|s| is_printable(s))
+    }
+
+    /// Big-endian integer keys, e.g. `Map<u64, _>`
+    pub fn as_u64(&self) -> Option<u64> {
+        self.0.as_slice().try_into().ok().map(u64::from_be_bytes)
+    }
+
+    pub fn as_u128(&self) -> Option<u128> {
+        self.0.as_slice().try_into().ok().map(u128::from_be_bytes)
+    }
+}
+
+impl fmt::Display for KeyPart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(s) = self.as_str() {
+            write!(f, "{:?}", s)
+        } else if let Some(n) = self.as_u64() {
+            write!(f, "{}", n)
+        } else if let Some(n) = self.as_u128() {
+            write!(f, "{}", n)
+        } else {
+            write!(f, "0x{}", hex::encode(&self.0))
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DecodedKey {
+    Item {
+        name: String,
+    },
+    Map {
+        namespace: String,
+        key: Vec<KeyPart>,
+    },
+    /// Keys that match neither layout
+    Raw(Vec<u8>),
+}
+
+impl DecodedKey {
+    /// The `Item` name or `Map` namespace, if any
+    pub fn namespace(&self) -> Option<&str> {
+        match self {
+            DecodedKey::Item { name } => Some(name),
+            DecodedKey::Map { namespace, .. } => Some(namespace),
+            DecodedKey::Raw(_) => None,
+        }
+    }
+
+    /// Re-encodes the key into the raw bytes cw-storage-plus would use
+    pub fn encode(&self) -> Vec<u8> {
+        match self {
+            DecodedKey::Item { name } => name.as_bytes().to_vec(),
+            DecodedKey::Map { namespace, key } => {
+                let mut raw = length_prefixed(namespace.as_bytes());
+                if let Some((last, parts)) = key.split_last() {
+                    for part in parts {
+                        raw.extend(length_prefixed(&part.0));
+                    }
+                    raw.extend_from_slice(&last.0);
+                }
+                raw
+            }
+            DecodedKey::Raw(raw) => raw.clone(),
+        }
+    }
+}
+
+impl fmt::Display for DecodedKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodedKey::Item { name } => write!(f, "{}", name),
+            DecodedKey::Map { namespace, key } => {
+                let parts: Vec<String> = key.iter().map(|part| part.to_string()).collect();
+                write!(f, "{}[{}]", namespace, parts.join(", "))
+            }
+            DecodedKey::Raw(raw) => write!(f, "0x{}", hex::encode(raw)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedEntry {
+    pub key: DecodedKey,
+    pub raw_key: Vec<u8>,
+    /// The value as JSON, or a base64 string if it isn't JSON
+    pub value: Value,
+}
+
+impl fmt::Display for DecodedEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = {}", self.key, self.value)
+    }
+}
+
+pub fn decode_key(raw: &[u8]) -> DecodedKey {
+    if let Some((namespace, rest)) = split_length_prefixed(raw) {
+        if let Some(namespace) = std::str::from_utf8(namespace)
+            .ok()
+            .filter(|s| is_printable(s))
+        {
+            return DecodedKey::Map {
+                namespace: namespace.to_string(),
+                key: decode_key_parts(rest),
+            };
+        }
+    }
+    match std::str::from_utf8(raw).ok().filter(|s| is_printable(s)) {
+        Some(name) => DecodedKey::Item {
+            name: name.to_string(),
+        },
+        None => DecodedKey::Raw(raw.to_vec()),
+    }
+}
+
+pub fn decode_value(raw: &[u8]) -> Value {
+    serde_json::from_slice(raw).unwrap_or_else(|_| Value::String(base64::encode(raw)))
+}
+
+pub fn decode_records(records: Vec<Record>) -> Vec<DecodedEntry> {
+    records
+        .into_iter()
+        .map(|(k, v)| DecodedEntry {
+            key: decode_key(&k),
+            value: decode_value(&v),
+            raw_key: k,
+        })
+        .collect()
+}
+
+/// Decodes every entry in `storage`, e.g. a contract's `deps.storage` after `mock_state`
+pub fn decode_storage(storage: &dyn Storage) -> Vec<DecodedEntry> {
+    decode_records(storage.range(None, None, Order::Ascending).collect())
+}
+
+/// Splits the non-final key parts off greedily. Empty parts are never read as length
+/// prefixed, so that big-endian integers with leading zero bytes stay whole
+fn decode_key_parts(mut rest: &[u8]) -> Vec<KeyPart> {
+    let mut parts = vec![];
+    while let Some((part, tail)) = split_length_prefixed(rest) {
+        if part.is_empty() || tail.is_empty() {
+            break;
+        }
+        parts.push(KeyPart(part.to_vec()));
+        rest = tail;
+    }
+    parts.push(KeyPart(rest.to_vec()));
+    parts
+}
+
+pub(crate) fn split_length_prefixed(raw: &[u8]) -> Option<(&[u8], &[u8])> {
+    if raw.len() < 2 {
+        return None;
+    }
+    let len = u16::from_be_bytes([raw[0], raw[1]]) as usize;
+    let rest = &raw[2..];
+    if len == 0 || len > rest.len() {
+        return None;
+    }
+    Some(rest.split_at(len))
+}
+
+pub(crate) fn length_prefixed(part: &[u8]) -> Vec<u8> {
+    let len = u16::try_from(part.len()).expect("key part too long");
+    [&len.to_be_bytes()[..], part].concat()
+}
+
+fn is_printable(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_graphic() || c == ' ')
+}
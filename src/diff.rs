@@ -0,0 +1,187 @@
+//! Snapshots of `CustomApp` storage and the differences between them, grouped by contract.
+//!
+//! ```ignore
+//! let before = StorageSnapshot::new(&app);
+//! app.execute_contract(owner, fin.clone(), &msg, &[]).unwrap();
+//! let diff = before.diff(&StorageSnapshot::new(&app));
+//! assert_eq!(diff.namespaces(&fin), vec!["orders", "cur_order_idx"]);
+//! ```
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use cosmwasm_std::{Addr, Order, Record};
+
+use crate::{
+    decode::{decode_key, decode_value, DecodedKey},
+    mock::CustomApp,
+    state::{contract_storage_prefix, split_contract_key},
+};
+
+/// Raw storage of a `CustomApp` at one point in time
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StorageSnapshot {
+    records: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+impl StorageSnapshot {
+    /// Snapshots the entire storage, including bank and other module state
+    pub fn new(app: &CustomApp) -> Self {
+        let records = app.read_module(|_, _, storage| {
+            storage
+                .range(None, None, Order::Ascending)
+                .collect::<Vec<Record>>()
+        });
+        Self {
+            records: records.into_iter().collect(),
+        }
+    }
+
+    /// Snapshots the storage of a single contract
+    pub fn contract(app: &CustomApp, contract: &Addr) -> Self {
+        let prefix = contract_storage_prefix(contract);
+        Self {
+            records: app
+                .dump_wasm_raw(contract)
+                .into_iter()
+                .map(|(k, v)| ([prefix.as_slice(), &k[..]].concat(), v))
+                .collect(),
+        }
+    }
+
+    /// Changes from `self` to `after`
+    pub fn diff(&self, after: &StorageSnapshot) -> StorageDiff {
+        let mut diff = StorageDiff::default();
+        for (key, before) in &self.records {
+            match after.records.get(key) {
+                None => diff.push(key, Change::Removed(before.clone())),
+                Some(value) if value != before => {
+                    diff.push(key, Change::Changed(before.clone(), value.clone()))
+                }
+                Some(_) => {}
+            }
+        }
+        for (key, value) in &after.records {
+            if !self.records.contains_key(key) {
+                diff.push(key, Change::Added(value.clone()));
+            }
+        }
+        diff.sort();
+        diff
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+    Added(Vec<u8>),
+    /// `(before, after)`
+    Changed(Vec<u8>, Vec<u8>),
+    Removed(Vec<u8>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyChange {
+    /// The key, decoded relative to the contract's storage for contract keys
+    pub key: DecodedKey,
+    pub raw_key: Vec<u8>,
+    pub change: Change,
+}
+
+impl fmt::Display for KeyChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.change {
+            Change::Added(value) => write!(f, "+ {} = {}", self.key, decode_value(value)),
+            Change::Changed(before, after) => write!(
+                f,
+                "~ {}: {} -> {}",
+                self.key,
+                decode_value(before),
+                decode_value(after)
+            ),
+            Change::Removed(value) => write!(f, "- {} = {}", self.key, decode_value(value)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StorageDiff {
+    /// Changes to contract storage, keyed by contract address
+    pub contracts: BTreeMap<Addr, Vec<KeyChange>>,
+    /// Changes outside of contract storage, such as bank balances and contract metadata
+    pub other: Vec<KeyChange>,
+}
+
+impl StorageDiff {
+    pub fn is_empty(&self) -> bool {
+        self.contracts.is_empty() && self.other.is_empty()
+    }
+
+    /// Contracts whose storage changed
+    pub fn contract_addresses(&self) -> Vec<Addr> {
+        self.contracts.keys().cloned().collect()
+    }
+
+    pub fn contract(&self, contract: &Addr) -> &[KeyChange] {
+        self.contracts
+            .get(contract)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// `Item` names and `Map` namespaces touched in `contract`'s storage
+    pub fn namespaces(&self, contract: &Addr) -> Vec<String> {
+        self.contract(contract)
+            .iter()
+            .map(|change| match change.key.namespace() {
+                Some(namespace) => namespace.to_string(),
+                None => change.key.to_string(),
+            })
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .collect()
+    }
+
+    fn push(&mut self, key: &[u8], change: Change) {
+        match split_contract_key(key) {
+            Some((contract, contract_key)) => {
+                self.contracts.entry(contract).or_default().push(KeyChange {
+                    key: decode_key(contract_key),
+                    raw_key: key.to_vec(),
+                    change,
+                })
+            }
+            None => self.other.push(KeyChange {
+                key: decode_key(key),
+                raw_key: key.to_vec(),
+                change,
+            }),
+        }
+    }
+
+    fn sort(&mut self) {
+        for changes in self.contracts.values_mut() {
+            changes.sort_by(|a, b| a.raw_key.cmp(&b.raw_key));
+        }
+        self.other.sort_by(|a, b| a.raw_key.cmp(&b.raw_key));
+    }
+}
+
+impl fmt::Display for StorageDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (contract, changes) in &self.contracts {
+            writeln!(f, "{}:", contract)?;
+            for change in changes {
+                writeln!(f, "  {}", change)?;
+            }
+        }
+        if !self.other.is_empty() {
+            writeln!(f, "other:")?;
+            for change in &self.other {
+                writeln!(f, "  {}", change)?;
+            }
+        }
+        Ok(())
+    }
+}
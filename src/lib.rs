@@ -1,5 +1,10 @@
 pub mod address;
 pub mod api;
+pub mod assert;
+pub mod crypto;
+pub mod debug;
+pub mod decode;
+pub mod diff;
 pub mod fin;
 pub mod mock;
 pub mod orca;
@@ -1,21 +1,30 @@
-use std::{collections::HashMap, convert::TryInto};
+use std::collections::HashMap;
 
-use anyhow::Result as AnyResult;
+use anyhow::{anyhow, bail, Result as AnyResult};
+use bech32::Variant;
+use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
-    attr, testing::MockStorage, to_json_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, Empty,
-    Event, Uint128,
+    attr, from_json,
+    testing::{mock_env, MockStorage},
+    to_json_binary, Addr, BalanceResponse, BankMsg, BlockInfo, Coin, CosmosMsg, Decimal, Empty,
+    Event, QuerierWrapper, QueryRequest, StdError, Storage, Timestamp, Uint128,
 };
+use cw_storage_plus::Map;
 
 use cw_multi_test::{
-    App, AppResponse, BankKeeper, BankSudo, BasicAppBuilder, CosmosRouter, DistributionKeeper,
-    Module, StakeKeeper, SudoMsg, WasmKeeper,
+    App, AppResponse, BankKeeper, BankSudo, BasicAppBuilder, Contract, CosmosRouter,
+    DistributionKeeper, Module, StakeKeeper, SudoMsg, WasmKeeper,
 };
 
 use kujira::{
-    BankQuery, DenomMsg, ExchangeRateResponse, KujiraMsg, KujiraQuery, OracleQuery, SupplyResponse,
+    fee_address, AuthMsg, BankQuery, Denom, DenomMsg, ExchangeRateResponse, KujiraMsg, KujiraQuery,
+    OracleQuery, SupplyResponse,
 };
 
-use crate::{address::MockAddressGenerator, api::MockApiBech32};
+use crate::{
+    address::{AddressScheme, MockAddressGenerator},
+    api::MockApiBech32,
+};
 
 pub type CustomApp = App<
     BankKeeper,
@@ -28,39 +37,543 @@ pub type CustomApp = App<
 >;
 
 pub fn mock_app(balances: Vec<(Addr, Vec<Coin>)>) -> CustomApp {
-    let mut custom = KujiraModule {
-        oracle_prices: HashMap::new(),
-    };
-    custom.set_oracle_price(Decimal::from_ratio(1425u128, 100u128), "factory/owner/coll");
-    custom.set_oracle_price(Decimal::one(), "factory/contract0/uusk");
-
-    BasicAppBuilder::new_custom()
-        .with_custom(custom)
-        .with_api(MockApiBech32::new("kujira"))
-        .with_wasm(WasmKeeper::default().with_address_generator(MockAddressGenerator))
-        .build(|router, _, storage| {
-            for (addr, coins) in balances {
-                router.bank.init_balance(storage, &addr, coins).unwrap();
+    MockAppBuilder::new()
+        .with_balances(balances)
+        .with_oracle_price(Decimal::from_ratio(1425u128, 100u128), "factory/owner/coll")
+        .with_oracle_price(Decimal::one(), "factory/contract0/uusk")
+        .build()
+}
+
+/// Configures a `CustomApp` without the fixtures baked into `mock_app`
+pub struct MockAppBuilder {
+    prefix: String,
+    variant: Variant,
+    extra_prefixes: Vec<String>,
+    print_debug: Option<bool>,
+    address_scheme: AddressScheme,
+    block: BlockInfo,
+    balances: Vec<(Addr, Vec<Coin>)>,
+    oracle_prices: Vec<(Decimal, String)>,
+    denoms: Vec<(String, Addr)>,
+    supplies: Vec<(String, Uint128)>,
+    codes: Vec<Box<dyn Contract<KujiraMsg, KujiraQuery>>>,
+    kujira: KujiraModule,
+}
+
+impl Default for MockAppBuilder {
+    fn default() -> Self {
+        Self {
+            prefix: "kujira".to_string(),
+            variant: Variant::Bech32,
+            extra_prefixes: vec![],
+            print_debug: None,
+            address_scheme: AddressScheme::default(),
+            block: mock_env().block,
+            balances: vec![],
+            oracle_prices: vec![],
+            denoms: vec![],
+            supplies: vec![],
+            codes: vec![],
+            kujira: KujiraModule::default(),
+        }
+    }
+}
+
+impl MockAppBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    pub fn with_variant(mut self, variant: Variant) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    /// Also accepts addresses with `prefix` in `addr_validate` and `addr_canonicalize`
+    pub fn with_accepted_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.extra_prefixes.push(prefix.into());
+        self
+    }
+
+    /// Prints contract debug messages to stderr, overriding `api::DEBUG_ENV_VAR`
+    pub fn with_print_debug(mut self, print_debug: bool) -> Self {
+        self.print_debug = Some(print_debug);
+        self
+    }
+
+    pub fn with_address_scheme(mut self, address_scheme: AddressScheme) -> Self {
+        self.address_scheme = address_scheme;
+        self
+    }
+
+    pub fn with_block(mut self, block: BlockInfo) -> Self {
+        self.block = block;
+        self
+    }
+
+    pub fn with_height(mut self, height: u64) -> Self {
+        self.block.height = height;
+        self
+    }
+
+    pub fn with_time(mut self, time: Timestamp) -> Self {
+        self.block.time = time;
+        self
+    }
+
+    pub fn with_chain_id(mut self, chain_id: impl Into<String>) -> Self {
+        self.block.chain_id = chain_id.into();
+        self
+    }
+
+    pub fn with_balance(mut self, address: Addr, coins: Vec<Coin>) -> Self {
+        self.balances.push((address, coins));
+        self
+    }
+
+    pub fn with_balances(mut self, balances: Vec<(Addr, Vec<Coin>)>) -> Self {
+        self.balances.extend(balances);
+        self
+    }
+
+    pub fn with_oracle_price(mut self, price: Decimal, denom: &str) -> Self {
+        self.oracle_prices.push((price, denom.to_string()));
+        self
+    }
+
+    /// Registers a full denom, e.g. `factory/{creator}/{subdenom}`, as already created
+    pub fn with_denom(mut self, denom: &str, admin: Addr) -> Self {
+        self.denoms.push((denom.to_string(), admin));
+        self
+    }
+
+    /// Mints `amount` of a denom registered with `with_denom` to its admin, so that it counts
+    /// towards the bank supply
+    pub fn with_supply(mut self, denom: &str, amount: Uint128) -> Self {
+        self.supplies.push((denom.to_string(), amount));
+        self
+    }
+
+    /// Stores a contract code at build time. Codes get ids in the order they are added,
+    /// starting at 1, and are wrapped with `DebugLog::wrap`
+    pub fn with_code(mut self, code: Box<dyn Contract<KujiraMsg, KujiraQuery>>) -> Self {
+        self.codes.push(code);
+        self
+    }
+
+    /// Replaces the default `KujiraModule`, e.g. to set a denom creation fee or price feeds
+    pub fn with_kujira_module(mut self, kujira: KujiraModule) -> Self {
+        self.kujira = kujira;
+        self
+    }
+
+    pub fn build(self) -> CustomApp {
+        let MockAppBuilder {
+            prefix,
+            variant,
+            extra_prefixes,
+            print_debug,
+            address_scheme,
+            block,
+            balances,
+            oracle_prices,
+            denoms,
+            supplies,
+            codes,
+            kujira,
+        } = self;
+
+        let mut api = MockApiBech32::new(prefix)
+            .with_variant(variant)
+            .with_sequential_contracts(matches!(address_scheme, AddressScheme::Sequential));
+        for prefix in extra_prefixes {
+            api = api.with_accepted_prefix(prefix);
+        }
+        if let Some(print_debug) = print_debug {
+            api = api.with_print_debug(print_debug);
+        }
+
+        let mut app = BasicAppBuilder::new_custom()
+            .with_custom(kujira)
+            .with_api(api)
+            .with_block(block.clone())
+            .with_wasm(
+                WasmKeeper::default()
+                    .with_address_generator(MockAddressGenerator::new(address_scheme)),
+            )
+            .build(|router, api, storage| {
+                for (addr, coins) in balances {
+                    router.bank.init_balance(storage, &addr, coins).unwrap();
+                }
+                for (price, denom) in oracle_prices {
+                    router
+                        .custom
+                        .set_oracle_price(storage, price, &denom)
+                        .unwrap();
+                }
+                for (denom, admin) in denoms {
+                    router.custom.create_denom(storage, &denom, &admin).unwrap();
+                }
+                for (denom, amount) in supplies {
+                    let admin = router.custom.denom_admin(storage, &denom).unwrap();
+                    router
+                        .sudo(
+                            api,
+                            storage,
+                            &block,
+                            SudoMsg::Bank(BankSudo::Mint {
+                                to_address: admin.to_string(),
+                                amount: vec![Coin::new(amount.u128(), denom)],
+                            }),
+                        )
+                        .unwrap();
+                }
+            });
+
+        let debug_log = app.api().debug_log();
+        for code in codes {
+            app.store_code(debug_log.wrap(code));
+        }
+        app
+    }
+}
+
+/// Privileged messages handled by `KujiraModule::sudo`, see [`kujira_sudo`]
+#[cw_serde]
+pub enum KujiraSudoMsg {
+    SetOraclePrice { denom: String, price: Decimal },
+    RemoveOraclePrice { denom: String },
+    SetOraclePrices { prices: Vec<(String, Decimal)> },
+}
+
+/// Executes a `KujiraSudoMsg` against the app's `KujiraModule` at the current block.
+///
+/// cw-multi-test's `SudoMsg::Custom` carries no payload, so `App::sudo` can't reach custom
+/// modules; use this instead.
+///
+/// Unlike `App::sudo` this is not transactional: it writes straight to app storage, so an
+/// error part way through a message is not rolled back. The handlers only write oracle
+/// prices, which can't fail short of a storage error.
+pub fn kujira_sudo(app: &mut CustomApp, msg: KujiraSudoMsg) -> AnyResult<AppResponse> {
+    let block = app.block_info();
+    app.init_modules(|router, api, storage| {
+        router
+            .custom
+            .sudo::<KujiraMsg, KujiraQuery>(api, storage, &*router, &block, msg)
+    })
+}
+
+fn oracle_price_event(denom: &str, price: Option<Decimal>) -> Event {
+    Event::new("oracle_price").add_attributes(vec![
+        attr("denom", denom),
+        attr(
+            "price",
+            price
+                .map(|p| p.to_string())
+                .unwrap_or_else(|| "none".to_string()),
+        ),
+    ])
+}
+
+/// Maximum length of a tokenfactory subdenom, as enforced by the chain
+const MAX_SUBDENOM_LENGTH: usize = 44;
+
+/// Oracle exchange rates, keyed by denom
+const ORACLE_PRICES: Map<&str, Decimal> = Map::new("kujira_oracle_prices");
+
+/// Current admin of each created tokenfactory denom, keyed by the full denom
+const DENOM_ADMINS: Map<&str, Addr> = Map::new("kujira_denom_admins");
+
+/// Vesting schedules created with `AuthMsg::CreateVestingAccount`, keyed by the vesting account
+const VESTING_ACCOUNTS: Map<&Addr, VestingSchedule> = Map::new("kujira_vesting_accounts");
+
+/// Vesting schedule of an account created with `AuthMsg::CreateVestingAccount`. It is only
+/// bookkeeping: the bank keeper doesn't enforce it, so the account can spend its locked coins
+/// straight away
+#[cw_serde]
+pub struct VestingSchedule {
+    pub original_vesting: Vec<Coin>,
+    /// Unix timestamp in seconds of the block that created the account
+    pub start_time: u64,
+    /// Unix timestamp in seconds at which all coins are vested
+    pub end_time: u64,
+    /// A delayed account vests everything at `end_time`, otherwise vesting is linear
+    pub delayed: bool,
+}
+
+impl VestingSchedule {
+    /// Coins vested at `time` (unix seconds)
+    pub fn vested(&self, time: u64) -> Vec<Coin> {
+        if time >= self.end_time {
+            return self.original_vesting.clone();
+        }
+        if self.delayed || time <= self.start_time {
+            return vec![];
+        }
+        self.original_vesting
+            .iter()
+            .map(|c| Coin {
+                denom: c.denom.clone(),
+                amount: c
+                    .amount
+                    .multiply_ratio(time - self.start_time, self.end_time - self.start_time),
+            })
+            .filter(|c| !c.amount.is_zero())
+            .collect()
+    }
+
+    /// Coins still locked at `time` (unix seconds)
+    pub fn locked(&self, time: u64) -> Vec<Coin> {
+        let vested = self.vested(time);
+        self.original_vesting
+            .iter()
+            .map(|c| {
+                let vested = vested
+                    .iter()
+                    .find(|v| v.denom == c.denom)
+                    .map(|v| v.amount)
+                    .unwrap_or_default();
+                Coin {
+                    denom: c.denom.clone(),
+                    amount: c.amount - vested,
+                }
+            })
+            .filter(|c| !c.amount.is_zero())
+            .collect()
+    }
+}
+
+#[cw_serde]
+pub struct VestingBalances {
+    pub vested: Vec<Coin>,
+    pub locked: Vec<Coin>,
+}
+
+/// `block -> price`, for `PriceFeed::Custom`
+pub type PriceFn = Box<dyn Fn(&BlockInfo) -> Option<Decimal> + Send + Sync>;
+
+/// A scripted price path for a single denom, evaluated against the block at query time
+pub enum PriceFeed {
+    /// `(height, price)` points. Each price holds from its height until the next point
+    Height {
+        points: Vec<(u64, Decimal)>,
+        interpolate: bool,
+    },
+    /// `(unix seconds, price)` points. Each price holds from its time until the next point
+    Time {
+        points: Vec<(u64, Decimal)>,
+        interpolate: bool,
+    },
+    Custom(PriceFn),
+}
+
+impl PriceFeed {
+    /// Price at `block`, or `None` if the block is before the first point
+    pub fn price(&self, block: &BlockInfo) -> Option<Decimal> {
+        match self {
+            PriceFeed::Height {
+                points,
+                interpolate,
+            } => Self::price_at(points, block.height, *interpolate),
+            PriceFeed::Time {
+                points,
+                interpolate,
+            } => Self::price_at(points, block.time.seconds(), *interpolate),
+            PriceFeed::Custom(f) => f(block),
+        }
+    }
+
+    fn price_at(points: &[(u64, Decimal)], x: u64, interpolate: bool) -> Option<Decimal> {
+        let idx = points.iter().rposition(|(at, _)| *at <= x)?;
+        let (x0, p0) = points[idx];
+        match points.get(idx + 1) {
+            Some((x1, p1)) if interpolate => {
+                let ratio = Decimal::from_ratio(x - x0, x1 - x0);
+                if *p1 >= p0 {
+                    Some(p0 + (*p1 - p0) * ratio)
+                } else {
+                    Some(p0 - (p0 - *p1) * ratio)
+                }
             }
-        })
+            _ => Some(p0),
+        }
+    }
+
+    fn sort(&mut self) {
+        match self {
+            PriceFeed::Height { points, .. } | PriceFeed::Time { points, .. } => {
+                points.sort_by_key(|(at, _)| *at)
+            }
+            PriceFeed::Custom(_) => {}
+        }
+    }
 }
 
 pub struct KujiraModule {
-    pub oracle_prices: HashMap<String, Decimal>,
+    /// Scripted price paths, which take precedence over stored oracle prices
+    pub price_feeds: HashMap<String, PriceFeed>,
+    /// Fee charged to the sender of `DenomMsg::Create`, if any
+    pub denom_creation_fee: Option<Coin>,
+    /// Recipient of the denom creation fee
+    pub fee_collector: Addr,
+    /// When set, exchange rate queries for denoms without a price return zero instead of an
+    /// error
+    pub lenient_oracle: bool,
+}
+
+impl Default for KujiraModule {
+    fn default() -> Self {
+        Self {
+            price_feeds: HashMap::new(),
+            denom_creation_fee: None,
+            fee_collector: fee_address(),
+            lenient_oracle: false,
+        }
+    }
 }
 
 impl KujiraModule {
-    pub fn set_oracle_price(&mut self, price: Decimal, denom: &str) {
-        self.oracle_prices.insert(denom.to_string(), price);
+    pub fn set_denom_creation_fee(&mut self, fee: Option<Coin>) {
+        self.denom_creation_fee = fee;
+    }
+
+    pub fn set_fee_collector(&mut self, fee_collector: Addr) {
+        self.fee_collector = fee_collector;
+    }
+
+    pub fn set_lenient_oracle(&mut self, lenient: bool) {
+        self.lenient_oracle = lenient;
+    }
+
+    pub fn set_price_feed(&mut self, denom: &str, mut feed: PriceFeed) {
+        feed.sort();
+        self.price_feeds.insert(denom.to_string(), feed);
+    }
+
+    pub fn remove_price_feed(&mut self, denom: &str) {
+        self.price_feeds.remove(denom);
+    }
+
+    pub fn set_oracle_price(
+        &self,
+        storage: &mut dyn Storage,
+        price: Decimal,
+        denom: &str,
+    ) -> AnyResult<()> {
+        Ok(ORACLE_PRICES.save(storage, denom, &price)?)
+    }
+
+    pub fn remove_oracle_price(&self, storage: &mut dyn Storage, denom: &str) {
+        ORACLE_PRICES.remove(storage, denom)
+    }
+
+    pub fn oracle_price(&self, storage: &dyn Storage, denom: &str) -> AnyResult<Option<Decimal>> {
+        Ok(ORACLE_PRICES.may_load(storage, denom)?)
+    }
+
+    /// Registers a full denom as created, with `admin` as its admin
+    pub fn create_denom(
+        &self,
+        storage: &mut dyn Storage,
+        denom: &str,
+        admin: &Addr,
+    ) -> AnyResult<()> {
+        if DENOM_ADMINS.has(storage, denom) {
+            bail!("denom {} already exists", denom);
+        }
+        Ok(DENOM_ADMINS.save(storage, denom, admin)?)
+    }
+
+    /// Returns the admin of `denom`, failing if the denom was never created
+    pub fn denom_admin(&self, storage: &dyn Storage, denom: &str) -> AnyResult<Addr> {
+        match DENOM_ADMINS.may_load(storage, denom)? {
+            Some(admin) => Ok(admin),
+            None => bail!("denom {} does not exist", denom),
+        }
+    }
+
+    pub fn vesting_schedule(
+        &self,
+        storage: &dyn Storage,
+        address: &Addr,
+    ) -> AnyResult<Option<VestingSchedule>> {
+        Ok(VESTING_ACCOUNTS.may_load(storage, address)?)
+    }
+
+    /// Returns the vested and locked coins of a vesting account at the time of `block`.
+    /// Accounts without a vesting schedule have nothing vested or locked. Locked coins are not
+    /// held back from the account's bank balance, see `VestingSchedule`
+    pub fn vesting_balances(
+        &self,
+        storage: &dyn Storage,
+        block: &BlockInfo,
+        address: &Addr,
+    ) -> AnyResult<VestingBalances> {
+        let time = block.time.seconds();
+        Ok(match self.vesting_schedule(storage, address)? {
+            Some(schedule) => VestingBalances {
+                vested: schedule.vested(time),
+                locked: schedule.locked(time),
+            },
+            None => VestingBalances {
+                vested: vec![],
+                locked: vec![],
+            },
+        })
+    }
+
+    fn assert_denom_admin(
+        &self,
+        storage: &dyn Storage,
+        denom: &Denom,
+        sender: &Addr,
+    ) -> AnyResult<()> {
+        let admin = self.denom_admin(storage, denom.as_ref())?;
+        if admin != *sender {
+            bail!(
+                "unauthorized: {} is not the admin of {} (admin: {})",
+                sender,
+                denom,
+                admin
+            );
+        }
+        Ok(())
     }
 }
 
+/// Builds the full `factory/{creator}/{subdenom}` denom, validating the subdenom
+pub fn factory_denom(creator: &Addr, subdenom: &str) -> AnyResult<String> {
+    if subdenom.is_empty() || subdenom.len() > MAX_SUBDENOM_LENGTH {
+        bail!(
+            "invalid subdenom {:?}: length must be between 1 and {}",
+            subdenom,
+            MAX_SUBDENOM_LENGTH
+        );
+    }
+    if let Some(c) = subdenom
+        .chars()
+        .find(|c| !c.is_ascii_alphanumeric() && !"./:_-".contains(*c))
+    {
+        bail!(
+            "invalid subdenom {:?}: character {:?} not allowed",
+            subdenom,
+            c
+        );
+    }
+    Ok(format!("factory/{}/{}", creator, subdenom))
+}
+
 impl Module for KujiraModule {
     type ExecT = KujiraMsg;
 
     type QueryT = KujiraQuery;
 
-    type SudoT = Empty;
+    type SudoT = KujiraSudoMsg;
 
     fn execute<ExecC, QueryC>(
         &self,
@@ -81,29 +594,114 @@ impl Module for KujiraModule {
         QueryC: cosmwasm_std::CustomQuery + serde::de::DeserializeOwned + 'static,
     {
         match msg {
-            KujiraMsg::Auth(_) => todo!(),
+            KujiraMsg::Auth(a) => match a {
+                AuthMsg::CreateVestingAccount {
+                    to_address,
+                    amount,
+                    end_time,
+                    delayed,
+                } => {
+                    let to_address = api.addr_validate(to_address.as_str())?;
+                    if amount.is_empty() || amount.iter().any(|c| c.amount.is_zero()) {
+                        bail!("invalid vesting amount: {:?}", amount);
+                    }
+                    let delayed = delayed.unwrap_or(false);
+                    let start_time = block.time.seconds();
+                    let end_time = end_time
+                        .map(|t| t.seconds())
+                        .filter(|t| *t > start_time)
+                        .ok_or_else(|| {
+                            anyhow!(
+                                "invalid end time {:?}: must be after {}",
+                                end_time,
+                                start_time
+                            )
+                        })?;
+                    if VESTING_ACCOUNTS.has(storage, &to_address) {
+                        bail!("vesting account {} already exists", to_address);
+                    }
+
+                    let res = router.execute(
+                        api,
+                        storage,
+                        block,
+                        sender,
+                        CosmosMsg::Bank(BankMsg::Send {
+                            to_address: to_address.to_string(),
+                            amount: amount.clone(),
+                        }),
+                    )?;
+                    VESTING_ACCOUNTS.save(
+                        storage,
+                        &to_address,
+                        &VestingSchedule {
+                            original_vesting: amount.clone(),
+                            start_time,
+                            end_time,
+                            delayed,
+                        },
+                    )?;
+
+                    let mut events = res.events;
+                    events.push(Event::new("create_vesting_account").add_attributes(vec![
+                        attr("to_address", to_address),
+                        attr(
+                            "amount",
+                            amount
+                                .iter()
+                                .map(|c| c.to_string())
+                                .collect::<Vec<String>>()
+                                .join(","),
+                        ),
+                        attr("end_time", end_time.to_string()),
+                        attr("delayed", delayed.to_string()),
+                    ]));
+                    Ok(AppResponse { events, data: None })
+                }
+            },
             KujiraMsg::Denom(d) => match d {
                 DenomMsg::Create { subdenom } => {
-                    storage.set(subdenom.as_bytes(), &Uint128::zero().to_be_bytes());
+                    let denom = factory_denom(&sender, subdenom.as_ref())?;
+                    if DENOM_ADMINS.has(storage, &denom) {
+                        bail!("denom {} already exists", denom);
+                    }
+                    let mut events = vec![];
+                    if let Some(fee) = &self.denom_creation_fee {
+                        let res = router
+                            .execute(
+                                api,
+                                storage,
+                                block,
+                                sender.clone(),
+                                CosmosMsg::Bank(BankMsg::Send {
+                                    to_address: self.fee_collector.to_string(),
+                                    amount: vec![fee.clone()],
+                                }),
+                            )
+                            .map_err(|err| match err.downcast_ref::<StdError>() {
+                                // the bank keeper reports an insufficient balance as an overflow
+                                Some(StdError::Overflow { .. }) => err.context(format!(
+                                    "insufficient funds to pay denom creation fee of {}",
+                                    fee
+                                )),
+                                _ => err,
+                            })?;
+                        events.extend(res.events);
+                    }
+                    self.create_denom(storage, &denom, &sender)?;
 
-                    Ok(AppResponse {
-                        events: vec![],
-                        data: None,
-                    })
+                    events.push(Event::new("create_denom").add_attributes(vec![
+                        attr("creator", sender),
+                        attr("new_token_denom", denom),
+                    ]));
+                    Ok(AppResponse { events, data: None })
                 }
                 DenomMsg::Mint {
                     amount,
                     denom,
                     recipient,
                 } => {
-                    let mut supply = storage
-                        .get(denom.as_bytes())
-                        .map(|bz| u128::from_be_bytes(bz.try_into().unwrap()))
-                        .map(Uint128::from)
-                        .unwrap_or_default();
-
-                    supply += amount;
-                    storage.set(denom.as_bytes(), &supply.to_be_bytes());
+                    self.assert_denom_admin(storage, &denom, &sender)?;
                     router.sudo(
                         api,
                         storage,
@@ -123,14 +721,25 @@ impl Module for KujiraModule {
                     })
                 }
                 DenomMsg::Burn { denom, amount } => {
-                    let mut supply = storage
-                        .get(denom.as_bytes())
-                        .map(|bz| u128::from_be_bytes(bz.try_into().unwrap()))
-                        .map(Uint128::from)
-                        .unwrap_or_default();
-                    println!("Burning {} of {}", amount, denom);
-                    supply -= amount;
-                    storage.set(denom.as_bytes(), &supply.to_be_bytes());
+                    self.assert_denom_admin(storage, &denom, &sender)?;
+                    let balance: BalanceResponse = from_json(router.query(
+                        api,
+                        storage,
+                        block,
+                        QueryRequest::Bank(cosmwasm_std::BankQuery::Balance {
+                            address: sender.to_string(),
+                            denom: denom.to_string(),
+                        }),
+                    )?)?;
+                    if balance.amount.amount < amount {
+                        bail!(
+                            "insufficient funds to burn {}{}: {} has {}",
+                            amount,
+                            denom,
+                            sender,
+                            balance.amount
+                        );
+                    }
 
                     router.execute(
                         api,
@@ -150,7 +759,19 @@ impl Module for KujiraModule {
                         data: None,
                     })
                 }
-                _ => todo!(),
+                DenomMsg::ChangeAdmin { denom, address } => {
+                    self.assert_denom_admin(storage, &denom, &sender)?;
+                    let address = api.addr_validate(address.as_str())?;
+                    DENOM_ADMINS.save(storage, denom.as_ref(), &address)?;
+
+                    Ok(AppResponse {
+                        events: vec![Event::new("change_admin").add_attributes(vec![
+                            attr("denom", denom.to_string()),
+                            attr("new_admin", address),
+                        ])],
+                        data: None,
+                    })
+                }
             },
         }
     }
@@ -158,10 +779,10 @@ impl Module for KujiraModule {
     fn sudo<ExecC, QueryC>(
         &self,
         _api: &dyn cosmwasm_std::Api,
-        _storage: &mut dyn cosmwasm_std::Storage,
+        storage: &mut dyn cosmwasm_std::Storage,
         _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
         _block: &cosmwasm_std::BlockInfo,
-        _msg: Self::SudoT,
+        msg: Self::SudoT,
     ) -> AnyResult<AppResponse>
     where
         ExecC: std::fmt::Debug
@@ -172,34 +793,62 @@ impl Module for KujiraModule {
             + 'static,
         QueryC: cosmwasm_std::CustomQuery + serde::de::DeserializeOwned + 'static,
     {
-        todo!()
+        let events = match msg {
+            KujiraSudoMsg::SetOraclePrice { denom, price } => {
+                self.set_oracle_price(storage, price, &denom)?;
+                vec![oracle_price_event(&denom, Some(price))]
+            }
+            KujiraSudoMsg::RemoveOraclePrice { denom } => {
+                self.remove_oracle_price(storage, &denom);
+                vec![oracle_price_event(&denom, None)]
+            }
+            KujiraSudoMsg::SetOraclePrices { prices } => prices
+                .into_iter()
+                .map(|(denom, price)| {
+                    self.set_oracle_price(storage, price, &denom)?;
+                    Ok(oracle_price_event(&denom, Some(price)))
+                })
+                .collect::<AnyResult<Vec<Event>>>()?,
+        };
+
+        Ok(AppResponse { events, data: None })
     }
 
     fn query(
         &self,
         _api: &dyn cosmwasm_std::Api,
         storage: &dyn cosmwasm_std::Storage,
-        _querier: &dyn cosmwasm_std::Querier,
-        _block: &cosmwasm_std::BlockInfo,
+        querier: &dyn cosmwasm_std::Querier,
+        block: &cosmwasm_std::BlockInfo,
         request: Self::QueryT,
     ) -> AnyResult<cosmwasm_std::Binary> {
         match request {
             KujiraQuery::Bank(b) => match b {
                 BankQuery::Supply { denom } => {
-                    let supply = storage
-                        .get(denom.as_bytes())
-                        .map(|bz| u128::from_be_bytes(bz.try_into().unwrap()))
-                        .unwrap_or_default();
-
-                    Ok(to_json_binary(&SupplyResponse {
-                        amount: denom.coin(&Uint128::from(supply)),
-                    })?)
+                    // Total supply is whatever the bank keeper holds, however it got there
+                    let amount =
+                        QuerierWrapper::<Empty>::new(querier).query_supply(denom.to_string())?;
+
+                    Ok(to_json_binary(&SupplyResponse { amount })?)
                 }
             },
             KujiraQuery::Oracle(o) => match o {
-                OracleQuery::ExchangeRate { denom } => Ok(to_json_binary(&ExchangeRateResponse {
-                    rate: *self.oracle_prices.get(&denom).unwrap_or(&Decimal::zero()),
-                })?),
+                OracleQuery::ExchangeRate { denom } => {
+                    let feed_price = self
+                        .price_feeds
+                        .get(&denom)
+                        .and_then(|feed| feed.price(block));
+                    let price = match feed_price {
+                        Some(price) => Some(price),
+                        None => self.oracle_price(storage, &denom)?,
+                    };
+                    let rate = match price {
+                        Some(rate) => rate,
+                        None if self.lenient_oracle => Decimal::zero(),
+                        None => bail!("unknown denom: no oracle price for {}", denom),
+                    };
+                    Ok(to_json_binary(&ExchangeRateResponse { rate })?)
+                }
             },
         }
     }
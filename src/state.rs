@@ -1,12 +1,32 @@
-use std::{fs::File, io::BufReader};
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::BufReader,
+    path::{Path, PathBuf},
+};
 
+use anyhow::{bail, Context, Result as AnyResult};
 use cosmwasm_std::{
     testing::{MockApi, MockQuerier, MockStorage},
-    CustomQuery, OwnedDeps, Storage,
+    Addr, Binary, CustomQuery, Order, OwnedDeps, Record, Storage,
 };
+use cw_multi_test::ContractData;
+use cw_storage_plus::Map;
 use schemars::JsonSchema;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
+use crate::{
+    address::MockAddressGenerator,
+    decode::{length_prefixed, split_length_prefixed},
+    mock::CustomApp,
+};
+
+/// Namespace of cw-multi-test's `WasmKeeper` in app storage
+const NAMESPACE_WASM: &[u8] = b"wasm";
+
+/// Contract metadata as stored by cw-multi-test's `WasmKeeper`, under `NAMESPACE_WASM`
+const CONTRACTS: Map<&Addr, ContractData> = Map::new("contracts");
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 struct StateExport {
     models: Vec<StateModel>,
@@ -21,24 +41,350 @@ struct StateModel {
 }
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 struct StatePagination {
-    next_key: Option<Vec<u8>>,
+    /// Raw key of the first model on the next page, if any
+    next_key: Option<Binary>,
     total: String,
 }
 
+/// Loads `./src/testing/states/{file}.json`, relative to the working directory, into `deps`.
+/// Panics with the file and offending entry if the export can't be loaded
 pub fn mock_state<C: CustomQuery + DeserializeOwned>(
     deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier<C>, C>,
     file: &str,
 ) {
-    let file = File::open(format!("./src/testing/states/{file}.json")).unwrap();
+    load_state_file(
+        &mut deps.storage,
+        format!("./src/testing/states/{file}.json"),
+    )
+    .unwrap_or_else(|err| panic!("Loading mock state failed: {:?}", err))
+}
+
+/// Loads a state export from `path` into `storage`
+pub fn load_state_file(storage: &mut dyn Storage, path: impl AsRef<Path>) -> AnyResult<()> {
+    let (source, export) = read_export_file(path.as_ref())?;
+    write_export(storage, &[], &source, export)
+}
+
+/// Loads the pages of a paginated state export into `storage`, checking that consecutive
+/// pages line up and that the number of models matches the reported total
+pub fn load_state_pages<P: AsRef<Path>>(storage: &mut dyn Storage, paths: &[P]) -> AnyResult<()> {
+    for (source, export) in read_pages(paths)? {
+        write_export(storage, &[], &source, export)?;
+    }
+    Ok(())
+}
+
+/// Loads every `.json` page in `dir` into `storage`, ordered by file name. Zero-pad page
+/// numbers so that `page-10.json` sorts after `page-09.json`
+pub fn load_state_dir(storage: &mut dyn Storage, dir: impl AsRef<Path>) -> AnyResult<()> {
+    load_state_pages(storage, &page_files(dir.as_ref())?)
+}
+
+/// Loads state export JSON into `storage`, e.g. fixtures embedded with `include_str!`
+pub fn load_state_str(storage: &mut dyn Storage, json: &str) -> AnyResult<()> {
+    let (source, export) = parse_export_str(json)?;
+    write_export(storage, &[], &source, export)
+}
+
+/// Loads a state export from `path` into the storage of `contract` in `app`, e.g. a mainnet
+/// export of a contract that has been registered with `register_contract`
+pub fn load_contract_state_file(
+    app: &mut CustomApp,
+    contract: &Addr,
+    path: impl AsRef<Path>,
+) -> AnyResult<()> {
+    let (source, export) = read_export_file(path.as_ref())?;
+    let prefix = contract_storage_prefix(contract);
+    app.init_modules(|_, _, storage| write_export(storage, &prefix, &source, export))
+}
+
+/// Loads the pages of a paginated state export into the storage of `contract` in `app`, with
+/// the same checks as `load_state_pages`
+pub fn load_contract_state_pages<P: AsRef<Path>>(
+    app: &mut CustomApp,
+    contract: &Addr,
+    paths: &[P],
+) -> AnyResult<()> {
+    let pages = read_pages(paths)?;
+    let prefix = contract_storage_prefix(contract);
+    app.init_modules(|_, _, storage| {
+        for (source, export) in pages {
+            write_export(storage, &prefix, &source, export)?;
+        }
+        Ok(())
+    })
+}
+
+/// Loads state export JSON into the storage of `contract` in `app`
+pub fn load_contract_state_str(app: &mut CustomApp, contract: &Addr, json: &str) -> AnyResult<()> {
+    let (source, export) = parse_export_str(json)?;
+    let prefix = contract_storage_prefix(contract);
+    app.init_modules(|_, _, storage| write_export(storage, &prefix, &source, export))
+}
+
+/// Registers a contract running stored `code_id` at a fixed `contract` address, such as the
+/// mainnet address of the contract whose state is being loaded. It is listed by
+/// `address::contracts` like an instantiated contract
+pub fn register_contract(
+    app: &mut CustomApp,
+    contract: &Addr,
+    code_id: u64,
+    creator: &Addr,
+    label: &str,
+) -> AnyResult<()> {
+    app.wrap()
+        .query_wasm_code_info(code_id)
+        .with_context(|| format!("code id {} is not stored", code_id))?;
+    if app.contract_data(contract).is_ok() {
+        bail!("contract {} already exists", contract);
+    }
+    let data = ContractData {
+        code_id,
+        creator: creator.clone(),
+        admin: None,
+        label: label.to_string(),
+        created: app.block_info().height,
+    };
+    app.init_modules(|_, _, storage| {
+        // `WasmKeeper` numbers instances by how many contracts it stores
+        let instance_id = instance_count(storage);
+        let key = [
+            length_prefixed(NAMESPACE_WASM),
+            CONTRACTS.key(contract).to_vec(),
+        ]
+        .concat();
+        storage.set(&key, &serde_json::to_vec(&data)?);
+        MockAddressGenerator::record(storage, contract, code_id, instance_id)
+    })
+}
+
+fn instance_count(storage: &dyn Storage) -> u64 {
+    let prefix = [
+        length_prefixed(NAMESPACE_WASM),
+        length_prefixed(b"contracts"),
+    ]
+    .concat();
+    storage
+        .range(Some(&prefix), None, Order::Ascending)
+        .take_while(|(k, _)| k.starts_with(&prefix))
+        .count() as u64
+}
+
+/// Exports the raw storage of `contract` in `app` as state export JSON, loadable with
+/// `mock_state` or `load_contract_state_str`
+pub fn export_contract_state(app: &CustomApp, contract: &Addr) -> AnyResult<String> {
+    to_export_json(app.dump_wasm_raw(contract))
+}
+
+/// Exports the entire storage of `app`, including all modules, as state export JSON
+pub fn export_app_state(app: &CustomApp) -> AnyResult<String> {
+    let records = app.read_module(|_, _, storage| {
+        storage
+            .range(None, None, Order::Ascending)
+            .collect::<Vec<Record>>()
+    });
+    to_export_json(records)
+}
+
+/// Writes the raw storage of `contract` in `app` to `path` as state export JSON
+pub fn export_contract_state_file(
+    app: &CustomApp,
+    contract: &Addr,
+    path: impl AsRef<Path>,
+) -> AnyResult<()> {
+    let path = path.as_ref();
+    fs::write(path, export_contract_state(app, contract)?)
+        .with_context(|| format!("{}: failed to write", path.display()))
+}
+
+fn to_export_json(records: Vec<Record>) -> AnyResult<String> {
+    let export = StateExport {
+        pagination: StatePagination {
+            next_key: None,
+            total: records.len().to_string(),
+        },
+        models: records
+            .into_iter()
+            .map(|(k, v)| StateModel {
+                key: hex::encode(k),
+                value: base64::encode(v),
+            })
+            .collect(),
+    };
+    Ok(serde_json::to_string_pretty(&export)?)
+}
+
+/// Prefix of every storage key of `contract` in a `CustomApp`'s storage, matching
+/// cw-multi-test's `WasmKeeper`
+pub fn contract_storage_prefix(contract: &Addr) -> Vec<u8> {
+    let namespace = [&b"contract_data/"[..], contract.as_bytes()].concat();
+    [length_prefixed(NAMESPACE_WASM), length_prefixed(&namespace)].concat()
+}
+
+/// Splits a key in a `CustomApp`'s storage into the contract that owns it and the contract's
+/// own key, the inverse of `contract_storage_prefix`
+pub fn split_contract_key(key: &[u8]) -> Option<(Addr, &[u8])> {
+    let rest = key.strip_prefix(length_prefixed(NAMESPACE_WASM).as_slice())?;
+    let (namespace, contract_key) = split_length_prefixed(rest)?;
+    let contract = namespace.strip_prefix(&b"contract_data/"[..])?;
+    let contract = std::str::from_utf8(contract).ok()?;
+    Some((Addr::unchecked(contract), contract_key))
+}
+
+fn read_export_file(path: &Path) -> AnyResult<(String, StateExport)> {
+    let source = path.display().to_string();
+    let file = File::open(path).with_context(|| format!("{}: failed to open", source))?;
     let reader = BufReader::new(file);
 
-    // Read the JSON contents of the file as an instance of `User`.
-    let u: StateExport = serde_json::from_reader(reader).unwrap();
+    let export: StateExport = serde_json::from_reader(reader)
+        .with_context(|| format!("{}: invalid state export", source))?;
+    Ok((source, export))
+}
+
+fn parse_export_str(json: &str) -> AnyResult<(String, StateExport)> {
+    let source = "<inline>".to_string();
+    let export: StateExport =
+        serde_json::from_str(json).with_context(|| format!("{}: invalid state export", source))?;
+    Ok((source, export))
+}
+
+fn page_files(dir: &Path) -> AnyResult<Vec<PathBuf>> {
+    let mut paths = fs::read_dir(dir)
+        .with_context(|| format!("{}: failed to read directory", dir.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("{}: failed to read directory", dir.display()))?;
+    paths.retain(|path| path.extension().and_then(|ext| ext.to_str()) == Some("json"));
+    paths.sort();
+    Ok(paths)
+}
+
+/// Reads all pages and checks them against each other before anything is written
+fn read_pages<P: AsRef<Path>>(paths: &[P]) -> AnyResult<Vec<(String, StateExport)>> {
+    if paths.is_empty() {
+        bail!("no state export pages given");
+    }
+    let pages = paths
+        .iter()
+        .map(|path| read_export_file(path.as_ref()))
+        .collect::<AnyResult<Vec<_>>>()?;
 
-    for m in u.models {
-        let k = hex::decode(&m.key).unwrap();
-        let v = base64::decode(&m.value).unwrap();
+    let mut problems = vec![];
+    let mut seen: HashMap<String, &str> = HashMap::new();
+    for (source, export) in &pages {
+        for m in &export.models {
+            let key = m.key.to_lowercase();
+            match seen.insert(key.clone(), source) {
+                Some(other) if other == source => {
+                    problems.push(format!("{} contains key {} more than once", source, key))
+                }
+                Some(other) => problems.push(format!(
+                    "{} overlaps {}: key {} appears in both",
+                    source, other, key
+                )),
+                None => {}
+            }
+        }
+    }
 
-        deps.storage.set(&k, &v)
+    for pair in pages.windows(2) {
+        let (source, export) = &pair[0];
+        let (next_source, next_export) = &pair[1];
+        let next_key = match &export.pagination.next_key {
+            Some(next_key) => hex::encode(next_key.as_slice()),
+            None => {
+                problems.push(format!(
+                    "{} has no next_key but is followed by {}",
+                    source, next_source
+                ));
+                continue;
+            }
+        };
+        match next_export.models.first() {
+            Some(first) if first.key.to_lowercase() == next_key => {}
+            Some(first) if first.key.to_lowercase() > next_key => problems.push(format!(
+                "missing page between {} and {}: expected first key {}, found {}",
+                source, next_source, next_key, first.key
+            )),
+            Some(first) => problems.push(format!(
+                "{} overlaps {}: expected first key {}, found {}",
+                next_source, source, next_key, first.key
+            )),
+            None => problems.push(format!("{} has no models", next_source)),
+        }
     }
+
+    let (last_source, last_export) = &pages[pages.len() - 1];
+    if let Some(next_key) = &last_export.pagination.next_key {
+        problems.push(format!(
+            "missing page after {}: next_key {}",
+            last_source,
+            hex::encode(next_key.as_slice())
+        ));
+    }
+
+    // Only the first page of an export carries the total, later pages report 0
+    let (first_source, first_export) = &pages[0];
+    let total: usize = first_export.pagination.total.parse().with_context(|| {
+        format!(
+            "{}: invalid total {:?}",
+            first_source, first_export.pagination.total
+        )
+    })?;
+    let loaded: usize = pages.iter().map(|(_, export)| export.models.len()).sum();
+    if total != 0 && total != loaded {
+        problems.push(format!(
+            "expected {} models in total, pages contain {}",
+            total, loaded
+        ));
+    }
+
+    if !problems.is_empty() {
+        bail!("invalid paginated state export:\n{}", problems.join("\n"));
+    }
+    Ok(pages)
+}
+
+fn write_export(
+    storage: &mut dyn Storage,
+    prefix: &[u8],
+    source: &str,
+    export: StateExport,
+) -> AnyResult<()> {
+    for (k, v) in export_records(source, export)? {
+        storage.set(&[prefix, &k[..]].concat(), &v)
+    }
+    Ok(())
+}
+
+/// Raw `(key, value)` pairs of a state export file
+pub fn state_file_records(path: impl AsRef<Path>) -> AnyResult<Vec<Record>> {
+    let (source, export) = read_export_file(path.as_ref())?;
+    export_records(&source, export)
+}
+
+/// Raw `(key, value)` pairs of state export JSON
+pub fn state_str_records(json: &str) -> AnyResult<Vec<Record>> {
+    let (source, export) = parse_export_str(json)?;
+    export_records(&source, export)
+}
+
+fn export_records(source: &str, export: StateExport) -> AnyResult<Vec<Record>> {
+    export
+        .models
+        .into_iter()
+        .enumerate()
+        .map(|(idx, m)| {
+            let k = hex::decode(&m.key).with_context(|| {
+                format!("{}: model {}: invalid hex key {:?}", source, idx, m.key)
+            })?;
+            let v = base64::decode(&m.value).with_context(|| {
+                format!(
+                    "{}: model {}: invalid base64 value {:?}",
+                    source, idx, m.value
+                )
+            })?;
+            Ok((k, v))
+        })
+        .collect()
 }
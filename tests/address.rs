@@ -0,0 +1,47 @@
+mod common;
+
+use cosmwasm_std::{coins, Addr, Api, BankMsg, Empty};
+use cw_multi_test::Executor;
+use kujira_rs_testing::{address::AddressScheme, mock::MockAppBuilder};
+
+#[test]
+fn sequential_contracts_can_be_executed() {
+    let owner = MockAppBuilder::new().build().api().addr_make("owner");
+    let mut app = MockAppBuilder::new()
+        .with_address_scheme(AddressScheme::Sequential)
+        .with_balance(owner.clone(), coins(100, "ukuji"))
+        .build();
+    let code_id = app.store_code(common::counter());
+    let contract = app
+        .instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "counter", None)
+        .unwrap();
+    assert_eq!(contract, Addr::unchecked("contract0"));
+
+    app.execute_contract(
+        owner.clone(),
+        contract.clone(),
+        &Empty {},
+        &coins(10, "ukuji"),
+    )
+    .unwrap();
+    app.execute(
+        owner,
+        BankMsg::Send {
+            to_address: contract.to_string(),
+            amount: coins(5, "ukuji"),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    let count: u64 = app.wrap().query_wasm_smart(&contract, &Empty {}).unwrap();
+    assert_eq!(count, 1);
+    let balance = app.wrap().query_balance(&contract, "ukuji").unwrap();
+    assert_eq!(balance.amount.u128(), 15);
+}
+
+#[test]
+fn sequential_addresses_are_rejected_by_default() {
+    let app = MockAppBuilder::new().build();
+    assert!(app.api().addr_validate("contract0").is_err());
+}
@@ -0,0 +1,128 @@
+use cosmwasm_std::{coin, coins, Addr, BankMsg, Event, Uint128};
+use cw_multi_test::{AppResponse, Executor};
+use kujira::DenomMsg;
+use kujira_rs_testing::{
+    assert::{assert_event, assert_mint, assert_no_event, assert_transfer},
+    mock::MockAppBuilder,
+};
+
+fn accounts() -> (Addr, Addr) {
+    let app = MockAppBuilder::new().build();
+    (app.api().addr_make("admin"), app.api().addr_make("other"))
+}
+
+fn mint_response() -> (AppResponse, Addr, String) {
+    let (admin, _) = accounts();
+    let denom = format!("factory/{}/uusk", admin);
+    let mut app = MockAppBuilder::new()
+        .with_denom(&denom, admin.clone())
+        .build();
+    let res = app
+        .execute(
+            admin.clone(),
+            DenomMsg::Mint {
+                denom: denom.as_str().into(),
+                amount: Uint128::new(100),
+                recipient: admin.clone(),
+            }
+            .into(),
+        )
+        .unwrap();
+    (res, admin, denom)
+}
+
+fn send_response() -> (AppResponse, Addr, Addr) {
+    let (sender, recipient) = accounts();
+    let mut app = MockAppBuilder::new()
+        .with_balance(sender.clone(), vec![coin(100, "ukuji"), coin(50, "uusk")])
+        .build();
+    let res = app
+        .execute(
+            sender.clone(),
+            BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: vec![coin(10, "ukuji"), coin(5, "uusk")],
+            }
+            .into(),
+        )
+        .unwrap();
+    (res, sender, recipient)
+}
+
+#[test]
+fn event_matches_attribute_subset() {
+    let (res, admin, _) = mint_response();
+
+    assert_event(
+        &res,
+        Event::new("mint")
+            .add_attribute("recipient", &admin)
+            .add_attribute("amount", "100"),
+    );
+    assert_no_event(&res, "burn");
+}
+
+#[test]
+#[should_panic(expected = "no `mint` event matching")]
+fn event_with_wrong_attribute_fails() {
+    let (res, _, _) = mint_response();
+
+    assert_event(&res, Event::new("mint").add_attribute("amount", "99"));
+}
+
+#[test]
+#[should_panic(expected = "emitted event types: [mint]")]
+fn missing_event_type_fails() {
+    let (res, _, _) = mint_response();
+
+    assert_event(&res, Event::new("burn"));
+}
+
+#[test]
+fn mint_matches() {
+    let (res, admin, denom) = mint_response();
+
+    assert_mint(&res, &denom, 100u128, &admin);
+}
+
+#[test]
+#[should_panic(expected = "no `mint` event matching")]
+fn mint_to_wrong_recipient_fails() {
+    let (res, _, denom) = mint_response();
+    let (_, other) = accounts();
+
+    assert_mint(&res, &denom, 100u128, &other);
+}
+
+#[test]
+fn transfer_matches_in_any_coin_order() {
+    let (res, sender, recipient) = send_response();
+
+    assert_transfer(
+        &res,
+        &sender,
+        &recipient,
+        vec![coin(5, "uusk"), coin(10, "ukuji")],
+    );
+}
+
+#[test]
+#[should_panic(expected = "no transfer matching")]
+fn transfer_with_wrong_amount_fails() {
+    let (res, sender, recipient) = send_response();
+
+    assert_transfer(&res, &sender, &recipient, coins(10, "ukuji"));
+}
+
+#[test]
+#[should_panic(expected = "no transfer matching")]
+fn transfer_from_wrong_sender_fails() {
+    let (res, sender, recipient) = send_response();
+
+    assert_transfer(
+        &res,
+        &recipient,
+        &sender,
+        vec![coin(10, "ukuji"), coin(5, "uusk")],
+    );
+}
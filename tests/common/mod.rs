@@ -0,0 +1,38 @@
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
+};
+use cw_multi_test::{Contract, ContractWrapper};
+use cw_storage_plus::Item;
+use kujira::{KujiraMsg, KujiraQuery};
+
+pub const COUNT: Item<u64> = Item::new("count");
+
+fn instantiate(
+    deps: DepsMut<KujiraQuery>,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: Empty,
+) -> StdResult<Response<KujiraMsg>> {
+    COUNT.save(deps.storage, &0)?;
+    Ok(Response::default())
+}
+
+fn execute(
+    deps: DepsMut<KujiraQuery>,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: Empty,
+) -> StdResult<Response<KujiraMsg>> {
+    let count = COUNT.update(deps.storage, |count| StdResult::Ok(count + 1))?;
+    deps.api.debug(&format!("count {}", count));
+    Ok(Response::default())
+}
+
+fn query(deps: Deps<KujiraQuery>, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    to_json_binary(&COUNT.load(deps.storage)?)
+}
+
+/// Counts its executions, logging the new count, and returns the count for any query
+pub fn counter() -> Box<dyn Contract<KujiraMsg, KujiraQuery>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
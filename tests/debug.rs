@@ -0,0 +1,22 @@
+mod common;
+
+use cosmwasm_std::Empty;
+use cw_multi_test::Executor;
+use kujira_rs_testing::mock::MockAppBuilder;
+
+#[test]
+fn builder_codes_are_attributed() {
+    let mut app = MockAppBuilder::new()
+        .with_print_debug(false)
+        .with_code(common::counter())
+        .build();
+    let owner = app.api().addr_make("owner");
+    let contract = app
+        .instantiate_contract(1, owner.clone(), &Empty {}, &[], "counter", None)
+        .unwrap();
+    app.execute_contract(owner, contract.clone(), &Empty {}, &[])
+        .unwrap();
+
+    let log = app.api().debug_log();
+    assert_eq!(log.messages_for(&contract), vec!["count 1".to_string()]);
+}
@@ -0,0 +1,103 @@
+use cosmwasm_std::{testing::MockStorage, Addr, Order, Storage};
+use cw_storage_plus::{Item, Map};
+use kujira_rs_testing::decode::{decode_key, decode_storage, DecodedKey, KeyPart};
+
+const CONFIG: Item<String> = Item::new("config");
+const BALANCES: Map<&str, u64> = Map::new("balances");
+const ORDERS: Map<u64, u64> = Map::new("orders");
+const POSITIONS: Map<(&Addr, u64), u64> = Map::new("positions");
+
+fn only_key(storage: &MockStorage) -> Vec<u8> {
+    let keys: Vec<Vec<u8>> = storage
+        .range(None, None, Order::Ascending)
+        .map(|(k, _)| k)
+        .collect();
+    assert_eq!(keys.len(), 1);
+    keys[0].clone()
+}
+
+fn assert_round_trip(raw: &[u8], expected: DecodedKey) {
+    let decoded = decode_key(raw);
+    assert_eq!(decoded, expected);
+    assert_eq!(decoded.encode(), raw);
+}
+
+#[test]
+fn item_round_trip() {
+    let mut storage = MockStorage::new();
+    CONFIG.save(&mut storage, &"owner".to_string()).unwrap();
+
+    assert_round_trip(
+        &only_key(&storage),
+        DecodedKey::Item {
+            name: "config".to_string(),
+        },
+    );
+}
+
+#[test]
+fn str_map_round_trip() {
+    let mut storage = MockStorage::new();
+    BALANCES.save(&mut storage, "alice", &100).unwrap();
+
+    assert_round_trip(
+        &only_key(&storage),
+        DecodedKey::Map {
+            namespace: "balances".to_string(),
+            key: vec![KeyPart(b"alice".to_vec())],
+        },
+    );
+}
+
+#[test]
+fn u64_map_round_trip() {
+    let mut storage = MockStorage::new();
+    ORDERS.save(&mut storage, 7, &100).unwrap();
+
+    let raw = only_key(&storage);
+    assert_round_trip(
+        &raw,
+        DecodedKey::Map {
+            namespace: "orders".to_string(),
+            key: vec![KeyPart(7u64.to_be_bytes().to_vec())],
+        },
+    );
+    match decode_key(&raw) {
+        DecodedKey::Map { key, .. } => assert_eq!(key[0].as_u64(), Some(7)),
+        key => panic!("decoded as {:?}", key),
+    }
+}
+
+#[test]
+fn composite_map_round_trip() {
+    let mut storage = MockStorage::new();
+    let owner = Addr::unchecked("kujira1owner");
+    POSITIONS.save(&mut storage, (&owner, 7), &100).unwrap();
+
+    assert_round_trip(
+        &only_key(&storage),
+        DecodedKey::Map {
+            namespace: "positions".to_string(),
+            key: vec![
+                KeyPart(b"kujira1owner".to_vec()),
+                KeyPart(7u64.to_be_bytes().to_vec()),
+            ],
+        },
+    );
+}
+
+#[test]
+fn decodes_storage_values() {
+    let mut storage = MockStorage::new();
+    CONFIG.save(&mut storage, &"owner".to_string()).unwrap();
+    BALANCES.save(&mut storage, "alice", &100).unwrap();
+
+    let entries: Vec<String> = decode_storage(&storage)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        entries,
+        vec!["balances[\"alice\"] = 100", "config = \"owner\""]
+    );
+}
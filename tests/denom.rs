@@ -0,0 +1,194 @@
+use cosmwasm_std::{coin, coins, Addr, QueryRequest, Uint128};
+use cw_multi_test::Executor;
+use kujira::{BankQuery, DenomMsg, KujiraQuery, SupplyResponse};
+use kujira_rs_testing::{
+    assert::assert_transfer,
+    mock::{CustomApp, KujiraModule, MockAppBuilder},
+};
+
+fn setup() -> (CustomApp, Addr, Addr, String) {
+    let app = MockAppBuilder::new().build();
+    let admin = app.api().addr_make("admin");
+    let other = app.api().addr_make("other");
+    let denom = format!("factory/{}/uusk", admin);
+    let app = MockAppBuilder::new()
+        .with_denom(&denom, admin.clone())
+        .build();
+    (app, admin, other, denom)
+}
+
+fn mint(app: &mut CustomApp, sender: &Addr, denom: &str, amount: u128) -> anyhow::Result<()> {
+    app.execute(
+        sender.clone(),
+        DenomMsg::Mint {
+            denom: denom.into(),
+            amount: Uint128::new(amount),
+            recipient: sender.clone(),
+        }
+        .into(),
+    )
+    .map(|_| ())
+}
+
+#[test]
+fn only_admin_can_mint() {
+    let (mut app, admin, other, denom) = setup();
+
+    mint(&mut app, &admin, &denom, 100).unwrap();
+    let err = mint(&mut app, &other, &denom, 100).unwrap_err();
+    assert!(format!("{:#}", err).contains("is not the admin of"));
+
+    let balance = app.wrap().query_balance(&admin, &denom).unwrap();
+    assert_eq!(balance.amount, Uint128::new(100));
+    let balance = app.wrap().query_balance(&other, &denom).unwrap();
+    assert_eq!(balance.amount, Uint128::zero());
+}
+
+#[test]
+fn change_admin_hands_over_minting() {
+    let (mut app, admin, other, denom) = setup();
+
+    let change = DenomMsg::ChangeAdmin {
+        denom: denom.as_str().into(),
+        address: other.clone(),
+    };
+    let err = app
+        .execute(other.clone(), change.clone().into())
+        .unwrap_err();
+    assert!(format!("{:#}", err).contains("is not the admin of"));
+    app.execute(admin.clone(), change.into()).unwrap();
+
+    mint(&mut app, &other, &denom, 100).unwrap();
+    mint(&mut app, &admin, &denom, 100).unwrap_err();
+}
+
+fn create(app: &mut CustomApp, sender: &Addr, subdenom: &str) -> anyhow::Result<()> {
+    app.execute(
+        sender.clone(),
+        DenomMsg::Create {
+            subdenom: subdenom.into(),
+        }
+        .into(),
+    )
+    .map(|_| ())
+}
+
+#[test]
+fn create_registers_factory_denom() {
+    let (mut app, _, other, _) = setup();
+
+    create(&mut app, &other, "uusk").unwrap();
+    let denom = format!("factory/{}/uusk", other);
+    mint(&mut app, &other, &denom, 100).unwrap();
+
+    let err = create(&mut app, &other, "uusk").unwrap_err();
+    assert!(format!("{:#}", err).contains("already exists"));
+}
+
+#[test]
+fn create_rejects_malformed_subdenoms() {
+    let (mut app, admin, _, _) = setup();
+
+    for subdenom in ["", "u usk", "uusk!", &"u".repeat(45)] {
+        let err = create(&mut app, &admin, subdenom).unwrap_err();
+        assert!(
+            format!("{:#}", err).contains("invalid subdenom"),
+            "{:?}: {:#}",
+            subdenom,
+            err
+        );
+    }
+    create(&mut app, &admin, &"u".repeat(44)).unwrap();
+    create(&mut app, &admin, "a.b/c:d_e-f").unwrap();
+}
+
+#[test]
+fn unregistered_denoms_cannot_be_minted_or_burned() {
+    let (mut app, admin, _, _) = setup();
+    let denom = format!("factory/{}/unknown", admin);
+
+    let err = mint(&mut app, &admin, &denom, 100).unwrap_err();
+    assert!(format!("{:#}", err).contains("does not exist"));
+    let err = burn(&mut app, &admin, &denom, 100).unwrap_err();
+    assert!(format!("{:#}", err).contains("does not exist"));
+}
+
+#[test]
+fn create_forwards_fee_to_collector() {
+    let app = MockAppBuilder::new().build();
+    let rich = app.api().addr_make("rich");
+    let poor = app.api().addr_make("poor");
+    let collector = app.api().addr_make("collector");
+    let mut kujira = KujiraModule::default();
+    kujira.set_denom_creation_fee(Some(coin(100, "ukuji")));
+    kujira.set_fee_collector(collector.clone());
+    let mut app = MockAppBuilder::new()
+        .with_kujira_module(kujira)
+        .with_balance(rich.clone(), coins(150, "ukuji"))
+        .with_balance(poor.clone(), coins(50, "ukuji"))
+        .build();
+
+    let res = app
+        .execute(
+            rich.clone(),
+            DenomMsg::Create {
+                subdenom: "uusk".into(),
+            }
+            .into(),
+        )
+        .unwrap();
+    assert_transfer(&res, &rich, &collector, coins(100, "ukuji"));
+    let balance = app.wrap().query_balance(&collector, "ukuji").unwrap();
+    assert_eq!(balance.amount, Uint128::new(100));
+
+    let err = create(&mut app, &poor, "uusk").unwrap_err();
+    assert!(format!("{:#}", err).contains("insufficient funds to pay denom creation fee"));
+    let denom = format!("factory/{}/uusk", poor);
+    let err = mint(&mut app, &poor, &denom, 1).unwrap_err();
+    assert!(format!("{:#}", err).contains("does not exist"));
+}
+
+fn supply(app: &CustomApp, denom: &str) -> Uint128 {
+    let res: SupplyResponse = app
+        .wrap()
+        .query(&QueryRequest::Custom(KujiraQuery::Bank(
+            BankQuery::Supply {
+                denom: denom.into(),
+            },
+        )))
+        .unwrap();
+    res.amount.amount
+}
+
+fn burn(app: &mut CustomApp, sender: &Addr, denom: &str, amount: u128) -> anyhow::Result<()> {
+    app.execute(
+        sender.clone(),
+        DenomMsg::Burn {
+            denom: denom.into(),
+            amount: Uint128::new(amount),
+        }
+        .into(),
+    )
+    .map(|_| ())
+}
+
+#[test]
+fn supply_tracks_mints_and_burns() {
+    let app = MockAppBuilder::new().build();
+    let admin = app.api().addr_make("admin");
+    let denom = format!("factory/{}/uusk", admin);
+    let mut app = MockAppBuilder::new()
+        .with_denom(&denom, admin.clone())
+        .with_supply(&denom, Uint128::new(1000))
+        .build();
+    assert_eq!(supply(&app, &denom), Uint128::new(1000));
+
+    mint(&mut app, &admin, &denom, 500).unwrap();
+    assert_eq!(supply(&app, &denom), Uint128::new(1500));
+    burn(&mut app, &admin, &denom, 200).unwrap();
+    assert_eq!(supply(&app, &denom), Uint128::new(1300));
+
+    let err = burn(&mut app, &admin, &denom, 1301).unwrap_err();
+    assert!(format!("{:#}", err).contains("insufficient funds to burn"));
+    assert_eq!(supply(&app, &denom), Uint128::new(1300));
+}
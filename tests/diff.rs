@@ -0,0 +1,51 @@
+mod common;
+
+use cosmwasm_std::{Addr, Empty};
+use cw_multi_test::Executor;
+use kujira_rs_testing::{
+    diff::{Change, StorageSnapshot},
+    mock::{CustomApp, MockAppBuilder},
+};
+
+fn setup() -> (CustomApp, Addr, Addr) {
+    let mut app = MockAppBuilder::new().build();
+    let owner = app.api().addr_make("owner");
+    let code_id = app.store_code(common::counter());
+    let counter = app
+        .instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "counter", None)
+        .unwrap();
+    (app, owner, counter)
+}
+
+#[test]
+fn diff_lists_changed_namespaces() {
+    let (mut app, owner, counter) = setup();
+
+    let before = StorageSnapshot::new(&app);
+    app.execute_contract(owner, counter.clone(), &Empty {}, &[])
+        .unwrap();
+    let diff = before.diff(&StorageSnapshot::new(&app));
+
+    assert_eq!(diff.contract_addresses(), vec![counter.clone()]);
+    assert_eq!(diff.namespaces(&counter), vec!["count"]);
+    assert_eq!(
+        diff.contract(&counter)[0].change,
+        Change::Changed(b"0".to_vec(), b"1".to_vec())
+    );
+    assert!(diff.other.is_empty());
+}
+
+#[test]
+fn contract_snapshot_diff() {
+    let (mut app, owner, counter) = setup();
+
+    let before = StorageSnapshot::contract(&app, &counter);
+    assert!(before.diff(&before).is_empty());
+
+    app.execute_contract(owner, counter.clone(), &Empty {}, &[])
+        .unwrap();
+    let diff = before.diff(&StorageSnapshot::contract(&app, &counter));
+
+    assert_eq!(diff.namespaces(&counter), vec!["count"]);
+    assert!(diff.other.is_empty());
+}
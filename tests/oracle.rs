@@ -0,0 +1,80 @@
+use cosmwasm_std::{Decimal, QueryRequest, StdResult, Timestamp};
+use kujira::{ExchangeRateResponse, KujiraQuery, OracleQuery};
+use kujira_rs_testing::mock::{CustomApp, KujiraModule, MockAppBuilder, PriceFeed};
+
+fn exchange_rate(app: &CustomApp, denom: &str) -> StdResult<Decimal> {
+    let res: ExchangeRateResponse = app.wrap().query(&QueryRequest::Custom(
+        KujiraQuery::Oracle(OracleQuery::ExchangeRate {
+            denom: denom.to_string(),
+        }),
+    ))?;
+    Ok(res.rate)
+}
+
+#[test]
+fn unknown_denom_fails_unless_lenient() {
+    let app = MockAppBuilder::new()
+        .with_oracle_price(Decimal::percent(150), "ukuji")
+        .build();
+    assert_eq!(exchange_rate(&app, "ukuji").unwrap(), Decimal::percent(150));
+    let err = exchange_rate(&app, "uatom").unwrap_err();
+    assert!(err.to_string().contains("no oracle price for uatom"));
+
+    let mut kujira = KujiraModule::default();
+    kujira.set_lenient_oracle(true);
+    let app = MockAppBuilder::new().with_kujira_module(kujira).build();
+    assert_eq!(exchange_rate(&app, "uatom").unwrap(), Decimal::zero());
+}
+
+#[test]
+fn height_feed_interpolates_between_points() {
+    let mut kujira = KujiraModule::default();
+    kujira.set_price_feed(
+        "ukuji",
+        PriceFeed::Height {
+            points: vec![(200, Decimal::percent(300)), (100, Decimal::percent(100))],
+            interpolate: true,
+        },
+    );
+    kujira.set_price_feed(
+        "uatom",
+        PriceFeed::Height {
+            points: vec![(100, Decimal::percent(200)), (200, Decimal::percent(100))],
+            interpolate: false,
+        },
+    );
+    let mut app = MockAppBuilder::new()
+        .with_kujira_module(kujira)
+        .with_height(50)
+        .with_oracle_price(Decimal::percent(50), "ukuji")
+        .build();
+
+    // before the first point the stored price applies
+    assert_eq!(exchange_rate(&app, "ukuji").unwrap(), Decimal::percent(50));
+
+    app.update_block(|block| block.height = 150);
+    assert_eq!(exchange_rate(&app, "ukuji").unwrap(), Decimal::percent(200));
+    assert_eq!(exchange_rate(&app, "uatom").unwrap(), Decimal::percent(200));
+
+    app.update_block(|block| block.height = 250);
+    assert_eq!(exchange_rate(&app, "ukuji").unwrap(), Decimal::percent(300));
+    assert_eq!(exchange_rate(&app, "uatom").unwrap(), Decimal::percent(100));
+}
+
+#[test]
+fn time_feed_interpolates_falling_prices() {
+    let app = MockAppBuilder::new().build();
+    let mut block = app.block_info();
+    let feed = PriceFeed::Time {
+        points: vec![
+            (1_000, Decimal::percent(200)),
+            (2_000, Decimal::percent(100)),
+        ],
+        interpolate: true,
+    };
+
+    block.time = Timestamp::from_seconds(1_250);
+    assert_eq!(feed.price(&block), Some(Decimal::percent(175)));
+    block.time = Timestamp::from_seconds(999);
+    assert_eq!(feed.price(&block), None);
+}
@@ -0,0 +1,147 @@
+mod common;
+
+use std::{fs, path::PathBuf};
+
+use cosmwasm_std::{testing::MockStorage, Empty, Storage};
+use cw_multi_test::Executor;
+use kujira_rs_testing::{
+    address::{address_by_label, contracts},
+    mock::MockAppBuilder,
+    state::{load_contract_state_str, load_state_pages, register_contract},
+};
+
+/// `count` = `5`
+const COUNTER_STATE: &str = r#"{
+    "models": [{ "key": "636f756e74", "value": "NQ==" }],
+    "pagination": { "next_key": null, "total": "1" }
+}"#;
+
+#[test]
+fn registered_contract_runs_loaded_state() {
+    let mut app = MockAppBuilder::new().build();
+    let owner = app.api().addr_make("owner");
+    let contract = app.api().addr_make("mainnet-counter");
+    let code_id = app.store_code(common::counter());
+
+    register_contract(&mut app, &contract, code_id, &owner, "counter").unwrap();
+    load_contract_state_str(&mut app, &contract, COUNTER_STATE).unwrap();
+    app.execute_contract(owner.clone(), contract.clone(), &Empty {}, &[])
+        .unwrap();
+
+    let count: u64 = app.wrap().query_wasm_smart(&contract, &Empty {}).unwrap();
+    assert_eq!(count, 6);
+
+    let other = app
+        .instantiate_contract(code_id, owner, &Empty {}, &[], "other", None)
+        .unwrap();
+    let entries = contracts(&app).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].address, contract);
+    assert_eq!(entries[0].instance_id, 0);
+    assert_eq!(entries[1].address, other);
+    assert_eq!(entries[1].instance_id, 1);
+    assert_eq!(address_by_label(&app, "counter").unwrap(), Some(contract));
+}
+
+#[test]
+fn register_contract_rejects_unknown_code_id() {
+    let mut app = MockAppBuilder::new().build();
+    let owner = app.api().addr_make("owner");
+    let contract = app.api().addr_make("mainnet-counter");
+
+    let err = register_contract(&mut app, &contract, 1, &owner, "counter").unwrap_err();
+    assert_eq!(err.to_string(), "code id 1 is not stored");
+}
+
+/// A page of models `a`, `b`, ... with hex keys and `next_key` as the raw key, base64 encoded
+fn page(keys: &[&str], next_key: Option<&str>, total: usize) -> String {
+    let models: Vec<String> = keys
+        .iter()
+        .map(|key| format!(r#"{{ "key": "{}", "value": "MQ==" }}"#, hex::encode(key)))
+        .collect();
+    let next_key = match next_key {
+        Some(key) => format!(r#""{}""#, base64::encode(key)),
+        None => "null".to_string(),
+    };
+    format!(
+        r#"{{ "models": [{}], "pagination": {{ "next_key": {}, "total": "{}" }} }}"#,
+        models.join(", "),
+        next_key,
+        total
+    )
+}
+
+/// Writes `pages` to a fresh directory as `page-0.json`, `page-1.json`, ...
+fn write_pages(name: &str, pages: &[String]) -> Vec<PathBuf> {
+    let dir =
+        std::env::temp_dir().join(format!("kujira-rs-testing-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    pages
+        .iter()
+        .enumerate()
+        .map(|(idx, page)| {
+            let path = dir.join(format!("page-{}.json", idx));
+            fs::write(&path, page).unwrap();
+            path
+        })
+        .collect()
+}
+
+fn load_pages_err(name: &str, pages: &[String]) -> String {
+    let paths = write_pages(name, pages);
+    let err = load_state_pages(&mut MockStorage::new(), &paths).unwrap_err();
+    format!("{:#}", err)
+}
+
+#[test]
+fn load_state_pages_loads_consecutive_pages() {
+    let paths = write_pages(
+        "consecutive",
+        &[page(&["a", "b"], Some("c"), 4), page(&["c", "d"], None, 0)],
+    );
+    let mut storage = MockStorage::new();
+    load_state_pages(&mut storage, &paths).unwrap();
+    assert_eq!(storage.get(b"d"), Some(b"1".to_vec()));
+}
+
+#[test]
+fn load_state_pages_reports_missing_page() {
+    let err = load_pages_err(
+        "missing",
+        &[page(&["a", "b"], Some("c"), 3), page(&["d"], None, 0)],
+    );
+    assert!(err.contains("missing page between"), "{}", err);
+
+    let err = load_pages_err("missing-last", &[page(&["a", "b"], Some("c"), 2)]);
+    assert!(err.contains("missing page after"), "{}", err);
+}
+
+#[test]
+fn load_state_pages_reports_overlap() {
+    let err = load_pages_err(
+        "overlap",
+        &[page(&["a", "b"], Some("c"), 4), page(&["b", "c"], None, 0)],
+    );
+    assert!(err.contains("key 62 appears in both"), "{}", err);
+    assert!(err.contains("expected first key 63, found 62"), "{}", err);
+}
+
+#[test]
+fn load_state_pages_reports_duplicate_within_page() {
+    let err = load_pages_err("duplicate", &[page(&["a", "a"], None, 2)]);
+    assert!(err.contains("contains key 61 more than once"), "{}", err);
+    assert!(!err.contains("overlaps"), "{}", err);
+}
+
+#[test]
+fn load_state_pages_reports_total_mismatch() {
+    let err = load_pages_err(
+        "total",
+        &[page(&["a", "b"], Some("c"), 5), page(&["c", "d"], None, 0)],
+    );
+    assert!(
+        err.contains("expected 5 models in total, pages contain 4"),
+        "{}",
+        err
+    );
+}
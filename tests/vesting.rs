@@ -0,0 +1,95 @@
+use cosmwasm_std::{coins, Addr, Coin};
+use cw_multi_test::Executor;
+use kujira::AuthMsg;
+use kujira_rs_testing::{
+    assert::assert_transfer,
+    mock::{CustomApp, MockAppBuilder, VestingBalances},
+};
+
+fn setup() -> (CustomApp, Addr, Addr) {
+    let app = MockAppBuilder::new().build();
+    let funder = app.api().addr_make("funder");
+    let recipient = app.api().addr_make("recipient");
+    let app = MockAppBuilder::new()
+        .with_balance(funder.clone(), coins(2000, "ukuji"))
+        .build();
+    (app, funder, recipient)
+}
+
+fn create(
+    app: &mut CustomApp,
+    funder: &Addr,
+    recipient: &Addr,
+    duration: Option<u64>,
+    delayed: Option<bool>,
+) -> anyhow::Result<cw_multi_test::AppResponse> {
+    let end_time = duration.map(|seconds| app.block_info().time.plus_seconds(seconds));
+    app.execute(
+        funder.clone(),
+        AuthMsg::CreateVestingAccount {
+            to_address: recipient.clone(),
+            amount: coins(1000, "ukuji"),
+            end_time,
+            delayed,
+        }
+        .into(),
+    )
+}
+
+fn balances_after(app: &mut CustomApp, seconds: u64, address: &Addr) -> VestingBalances {
+    app.update_block(|block| block.time = block.time.plus_seconds(seconds));
+    let block = app.block_info();
+    app.read_module(|router, _, storage| {
+        router
+            .custom
+            .vesting_balances(storage, &block, address)
+            .unwrap()
+    })
+}
+
+fn ukuji(amount: u128) -> Vec<Coin> {
+    coins(amount, "ukuji")
+}
+
+#[test]
+fn linear_vesting_unlocks_over_time() {
+    let (mut app, funder, recipient) = setup();
+
+    let res = create(&mut app, &funder, &recipient, Some(100), None).unwrap();
+    assert_transfer(&res, &funder, &recipient, ukuji(1000));
+
+    let balances = balances_after(&mut app, 25, &recipient);
+    assert_eq!(balances.vested, ukuji(250));
+    assert_eq!(balances.locked, ukuji(750));
+    let balances = balances_after(&mut app, 75, &recipient);
+    assert_eq!(balances.vested, ukuji(1000));
+    assert_eq!(balances.locked, vec![]);
+}
+
+#[test]
+fn delayed_vesting_unlocks_at_end_time() {
+    let (mut app, funder, recipient) = setup();
+
+    create(&mut app, &funder, &recipient, Some(100), Some(true)).unwrap();
+
+    let balances = balances_after(&mut app, 99, &recipient);
+    assert_eq!(balances.vested, vec![]);
+    assert_eq!(balances.locked, ukuji(1000));
+    let balances = balances_after(&mut app, 1, &recipient);
+    assert_eq!(balances.vested, ukuji(1000));
+    assert_eq!(balances.locked, vec![]);
+}
+
+#[test]
+fn create_rejects_bad_end_time_and_duplicates() {
+    let (mut app, funder, recipient) = setup();
+
+    let err = create(&mut app, &funder, &recipient, None, None).unwrap_err();
+    assert!(format!("{:#}", err).contains("invalid end time"));
+    let err = create(&mut app, &funder, &recipient, Some(0), None).unwrap_err();
+    assert!(format!("{:#}", err).contains("invalid end time"));
+
+    create(&mut app, &funder, &recipient, Some(100), None).unwrap();
+    let err = create(&mut app, &funder, &recipient, Some(100), None).unwrap_err();
+    assert!(format!("{:#}", err).contains("already exists"));
+}